@@ -0,0 +1,42 @@
+use solana_program::hash::hash;
+
+/// Returns the 8-byte discriminator Anchor prefixes instruction data with: the first 8 bytes of
+/// `sha256("global:<name>")`. Anchor hashes the snake_case instruction name, so camelCase names as they appear
+/// in IDLs, e.g. "mintNft", are converted first.
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    discriminator(&format!("global:{}", to_snake_case(name)))
+}
+
+/// Returns the 8-byte discriminator Anchor prefixes event data with: the first 8 bytes of
+/// `sha256("event:<Name>")`, using the event struct's name as written, e.g. "SaleEvent".
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    discriminator(&format!("event:{}", name))
+}
+
+fn discriminator(preimage: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(preimage.as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Convert a camelCase or PascalCase name to snake_case, keeping acronyms together ("mintNFT" -> "mint_nft").
+fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::with_capacity(name.len() + 4);
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
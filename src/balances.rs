@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    UiTransactionTokenBalance,
+};
+use std::collections::BTreeMap;
+
+use crate::filters::transaction_account_keys;
+
+/// The change in a token account's balance over a transaction, from `meta.pre_token_balances` and
+/// `meta.post_token_balances`. Amounts are in the token's base units.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenBalanceChange {
+    /// The token account address.
+    pub account: String,
+    pub mint: String,
+    /// The owner of the token account. Older transactions don't record owners.
+    pub owner: Option<String>,
+    /// The balance before the transaction, 0 if the account didn't exist yet.
+    pub pre_amount: u64,
+    /// The balance after the transaction, 0 if the account was closed.
+    pub post_amount: u64,
+}
+
+impl TokenBalanceChange {
+    pub fn delta(&self) -> i128 {
+        self.post_amount as i128 - self.pre_amount as i128
+    }
+}
+
+/// Returns the balance change of every token account listed in the transaction's token balances, in account
+/// order, including accounts whose balance didn't change.
+pub fn token_balance_changes(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Vec<TokenBalanceChange> {
+    let meta = match &tx.transaction.meta {
+        Some(meta) => meta,
+        None => return Vec::new(),
+    };
+    let account_keys = transaction_account_keys(tx);
+
+    let mut changes: BTreeMap<u8, TokenBalanceChange> = BTreeMap::new();
+
+    for (balance, is_post) in token_balances(&meta.pre_token_balances)
+        .map(|balance| (balance, false))
+        .chain(token_balances(&meta.post_token_balances).map(|balance| (balance, true)))
+    {
+        let amount = match balance.ui_token_amount.amount.parse::<u64>() {
+            Ok(amount) => amount,
+            Err(_) => continue,
+        };
+
+        let change = changes
+            .entry(balance.account_index)
+            .or_insert_with(|| TokenBalanceChange {
+                account: account_keys
+                    .get(balance.account_index as usize)
+                    .cloned()
+                    .unwrap_or_default(),
+                mint: balance.mint.clone(),
+                owner: None,
+                pre_amount: 0,
+                post_amount: 0,
+            });
+
+        if is_post {
+            change.post_amount = amount;
+        } else {
+            change.pre_amount = amount;
+        }
+        if let OptionSerializer::Some(owner) = &balance.owner {
+            change.owner = Some(owner.clone());
+        }
+    }
+
+    changes.into_values().collect()
+}
+
+fn token_balances(
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+) -> impl Iterator<Item = &UiTransactionTokenBalance> {
+    match balances {
+        OptionSerializer::Some(balances) => balances.iter(),
+        _ => [].iter(),
+    }
+}
+
+/// The change in an account's SOL balance over a transaction, from `meta.pre_balances` and `meta.post_balances`.
+/// The fee payer's change includes the transaction fee.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolBalanceChange {
+    pub account: String,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+}
+
+impl SolBalanceChange {
+    pub fn delta(&self) -> i128 {
+        self.post_lamports as i128 - self.pre_lamports as i128
+    }
+}
+
+/// Returns the SOL balance change of every account in the transaction, in account key order, including accounts
+/// whose balance didn't change.
+pub fn sol_balance_changes(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Vec<SolBalanceChange> {
+    let meta = match &tx.transaction.meta {
+        Some(meta) => meta,
+        None => return Vec::new(),
+    };
+
+    transaction_account_keys(tx)
+        .into_iter()
+        .zip(meta.pre_balances.iter().zip(meta.post_balances.iter()))
+        .map(|(account, (pre, post))| SolBalanceChange {
+            account,
+            pre_lamports: *pre,
+            post_lamports: *post,
+        })
+        .collect()
+}
+
+/// Returns the fee paid by the transaction in lamports.
+pub fn transaction_fee(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Option<u64> {
+    tx.transaction.meta.as_ref().map(|meta| meta.fee)
+}
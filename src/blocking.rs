@@ -0,0 +1,13 @@
+use crate::errors::CrawlError;
+
+/// Run a blocking call, such as a `TransactionStore` query or a checkpoint write, on tokio's blocking thread pool
+/// so it doesn't stall the runtime's worker threads.
+pub(crate) async fn run_blocking<T, F>(call: F) -> Result<T, CrawlError>
+where
+    F: FnOnce() -> Result<T, CrawlError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(call)
+        .await
+        .map_err(|err| CrawlError::BlockingTaskFailed(err.to_string()))?
+}
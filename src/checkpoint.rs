@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{errors::CrawlError, records::CrawlRecord, sources::parse_jsonl};
+
+/// A crawl's progress. The crawler appends a `CheckpointEntry` to the checkpoint file after every page of
+/// signatures and every batch of transactions, so an interrupted crawl can be picked up again with
+/// `Crawler::resume_from` and saving progress never rewrites what is already on disk. The records extracted so
+/// far are appended to a separate file next to it, see `records_path`.
+#[derive(Debug, Default)]
+pub struct Checkpoint {
+    /// The address being crawled.
+    pub address: String,
+    /// Pagination cursor: the oldest signature listed so far.
+    pub before: Option<String>,
+    /// Lower bound of the crawl, used by incremental crawls to stop at the previous run's newest signature.
+    pub until: Option<String>,
+    /// Set once every page of signatures has been listed.
+    pub listing_complete: bool,
+    /// All signatures listed so far, newest first.
+    pub signatures: Vec<String>,
+    /// Signatures whose transactions have already been fetched and processed.
+    pub processed: HashSet<String>,
+}
+
+/// One line of a checkpoint file. A checkpoint is the result of applying its entries in order.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "entry", rename_all = "snake_case")]
+pub enum CheckpointEntry {
+    /// The first line of every checkpoint file.
+    Started {
+        address: String,
+        until: Option<String>,
+    },
+    /// A page of signatures was listed, and `before` is the cursor of the next one.
+    Listed {
+        signatures: Vec<String>,
+        before: Option<String>,
+    },
+    /// The last page of signatures was listed.
+    ListingComplete,
+    /// A batch of transactions was processed.
+    Processed { signatures: Vec<String> },
+}
+
+impl Checkpoint {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Update the checkpoint with an entry.
+    pub fn apply(&mut self, entry: CheckpointEntry) {
+        match entry {
+            CheckpointEntry::Started { address, until } => {
+                self.address = address;
+                self.until = until;
+            }
+            CheckpointEntry::Listed { signatures, before } => {
+                self.signatures.extend(signatures);
+                if before.is_some() {
+                    self.before = before;
+                }
+            }
+            CheckpointEntry::ListingComplete => self.listing_complete = true,
+            CheckpointEntry::Processed { signatures } => self.processed.extend(signatures),
+        }
+    }
+
+    /// Load a checkpoint from a file, returning `None` if the file doesn't exist. An unfinished last entry left
+    /// by an interrupted append is ignored.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Option<Self>, CrawlError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let data = fs::read_to_string(path).map_err(|err| checkpoint_error(path, err))?;
+        let entries =
+            parse_jsonl::<CheckpointEntry>(&data).map_err(|err| checkpoint_error(path, err))?;
+        if !matches!(entries.first(), Some(CheckpointEntry::Started { .. })) {
+            return Err(checkpoint_error(
+                path,
+                "checkpoint doesn't start with a started entry",
+            ));
+        }
+
+        let mut checkpoint = Checkpoint::default();
+        for entry in entries {
+            checkpoint.apply(entry);
+        }
+
+        Ok(Some(checkpoint))
+    }
+
+    /// Save the whole checkpoint to a file, replacing its log with the fewest entries that describe it. The data
+    /// is written to a temporary file first and then renamed so an interrupted write never leaves a corrupt
+    /// checkpoint behind. The crawler does this once when a crawl starts, then appends to it with `append`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), CrawlError> {
+        let path = path.as_ref();
+        let tmp_path = tmp_path(path);
+
+        let mut entries = vec![CheckpointEntry::Started {
+            address: self.address.clone(),
+            until: self.until.clone(),
+        }];
+        if !self.signatures.is_empty() || self.before.is_some() {
+            entries.push(CheckpointEntry::Listed {
+                signatures: self.signatures.clone(),
+                before: self.before.clone(),
+            });
+        }
+        if self.listing_complete {
+            entries.push(CheckpointEntry::ListingComplete);
+        }
+        if !self.processed.is_empty() {
+            entries.push(CheckpointEntry::Processed {
+                signatures: self.processed.iter().cloned().collect(),
+            });
+        }
+
+        write_lines(&tmp_path, &entries, false)?;
+        fs::rename(&tmp_path, path).map_err(|err| checkpoint_error(path, err))?;
+
+        Ok(())
+    }
+
+    /// Append an entry to the checkpoint file at `path`.
+    pub fn append<P: AsRef<Path>>(path: P, entry: &CheckpointEntry) -> Result<(), CrawlError> {
+        write_lines(path.as_ref(), std::slice::from_ref(entry), true)
+    }
+
+    /// Load the records saved next to the checkpoint at `path`, keeping only the ones from processed
+    /// transactions. The records file is rewritten with just those, which drops the records of a batch that was
+    /// interrupted before the checkpoint was saved and starts an empty file for a new crawl.
+    pub fn load_records<P: AsRef<Path>>(&self, path: P) -> Result<Vec<CrawlRecord>, CrawlError> {
+        let path = records_path(path.as_ref());
+
+        let records = if path.exists() {
+            let data = fs::read_to_string(&path).map_err(|err| checkpoint_error(&path, err))?;
+            parse_jsonl::<CrawlRecord>(&data)
+                .map_err(|err| checkpoint_error(&path, err))?
+                .into_iter()
+                .filter(|record| self.processed.contains(&record.signature))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let tmp_path = tmp_path(&path);
+        write_lines(&tmp_path, &records, false)?;
+        fs::rename(&tmp_path, &path).map_err(|err| checkpoint_error(&path, err))?;
+
+        Ok(records)
+    }
+
+    /// Append records to the records file next to the checkpoint at `path`.
+    pub fn append_records<P: AsRef<Path>>(
+        path: P,
+        records: &[CrawlRecord],
+    ) -> Result<(), CrawlError> {
+        write_lines(&records_path(path.as_ref()), records, true)
+    }
+}
+
+/// The JSONL file the records of the crawl checkpointed at `path` are kept in, e.g. `crawl.json.records.jsonl`
+/// for `crawl.json`.
+pub fn records_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".records.jsonl");
+    path.with_file_name(file_name)
+}
+
+fn write_lines<T: Serialize>(path: &Path, lines: &[T], append: bool) -> Result<(), CrawlError> {
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .map_err(|err| checkpoint_error(path, err))?;
+    let mut writer = BufWriter::new(file);
+
+    for line in lines {
+        serde_json::to_writer(&mut writer, line).map_err(|err| checkpoint_error(path, err))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|err| checkpoint_error(path, err))?;
+    }
+
+    writer.flush().map_err(|err| checkpoint_error(path, err))
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+fn checkpoint_error<E: ToString>(path: &Path, err: E) -> CrawlError {
+    CrawlError::CheckpointFailed(err.to_string(), path.display().to_string())
+}
@@ -5,3 +5,9 @@ pub const CMV2_BOT_TAX_MSG: &str = "Candy Machine Botting is taxed at";
 pub const CREATE_MASTER_EDITION_DATA: &str = "ZbhHTZcMWdXcj";
 pub const CREATE_MASTER_EDITION_V3_DATA: &str = "xQfWWpLr8ajWF";
 pub const DEFAULT_CONCURRENCY_LIMIT: usize = 100;
+pub const SIGNATURES_PAGE_LIMIT: usize = 1000;
+pub const CHECKPOINT_BATCH_SIZE: usize = 1000;
+pub const MAX_RETRIES: usize = 10;
+pub const RETRY_BASE_DELAY_MS: u64 = 500;
+pub const RETRY_MAX_DELAY_MS: u64 = 8000;
+pub const RPC_TIMEOUT_SECS: u64 = 30;
@@ -1,19 +1,46 @@
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
 use rayon::prelude::*;
-use retry::{delay::Fixed, retry};
-use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
-use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::{
+    clock::{Slot, UnixTimestamp},
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::Signature,
+};
 use solana_transaction_status::{
     option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
-    EncodedTransaction, UiInstruction, UiMessage, UiParsedInstruction, UiTransactionEncoding,
+    EncodedTransaction, TransactionBinaryEncoding, UiCompiledInstruction, UiInstruction, UiMessage,
+    UiParsedInstruction, UiPartiallyDecodedInstruction,
 };
 use std::{
+    borrow::Cow,
     collections::{HashMap, HashSet},
+    fs::{self, OpenOptions},
+    future::Future,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
     str::FromStr,
-    sync::{Arc, Mutex},
+    sync::Arc,
+    time::Duration,
 };
 use tokio::sync::Semaphore;
 
-use crate::{constants::*, errors::CrawlError, filters::*};
+use crate::{
+    blocking::run_blocking,
+    checkpoint::{Checkpoint, CheckpointEntry},
+    constants::*,
+    errors::CrawlError,
+    extractors::{IxExtractor, IxField, TxExtractor},
+    filters::*,
+    idl::Idl,
+    logs::log_messages,
+    records::{crawled_accounts, CrawlRecord},
+    sinks::RecordSink,
+    sources::{clone_transaction, transaction_signature, FileSource, TransactionSource},
+    store::TransactionStore,
+};
 
 // Public API
 
@@ -21,13 +48,57 @@ use crate::{constants::*, errors::CrawlError, filters::*};
 /// and a unique set of the accounts is associated with it.
 pub type CrawledAccounts = HashMap<String, HashSet<String>>;
 
+/// The state carried between incremental crawls: every account found so far, the newest signature
+/// that has been crawled and the signatures that couldn't be fetched yet. Pass it to `Crawler::run_incremental`
+/// to only crawl transactions newer than it.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CrawlState {
+    pub accounts: CrawledAccounts,
+    pub newest_signature: Option<String>,
+    /// Signatures whose transactions failed to fetch. They are older than `newest_signature`, so the next
+    /// incremental crawl retries them instead of listing them again.
+    #[serde(default)]
+    pub failed: Vec<String>,
+}
+
+/// A summary of how complete a crawl was, returned next to the crawled accounts.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CrawlReport {
+    /// Signatures whose transactions could not be fetched, along with the error from the last attempt.
+    pub failed: Vec<(String, CrawlError)>,
+    /// Transactions that were fetched but could not be processed, skipped under `ErrorPolicy::Skip`.
+    pub skipped: Vec<(String, CrawlError)>,
+    /// Total number of retries used while listing signatures and fetching transactions.
+    pub retries: usize,
+}
+
+impl CrawlReport {
+    /// Returns true if every transaction was fetched.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// How the crawler handles transactions it can't process, such as ones in an unsupported encoding
+/// or with instructions that weren't parsed by the node.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorPolicy {
+    /// Skip the transaction and record it in the CrawlReport.
+    #[default]
+    Skip,
+    /// Stop the crawl and return the error.
+    Abort,
+}
+
 /// Instruction Accounts represent the specific accounts users wish to retrieve from an instruction.
 /// For unparsed instructions the user must specify the account index and the name they wish to it be labeled.
 /// For parsed instructions the users must specify the actual name as it's represented in the instruction:
 /// e.g. "mint" for the mint account in a SPL token call.
+/// For programs with an IDL added to the crawler, accounts can be named as they are in the IDL instruction.
 pub struct IxAccount {
     name: String,
     index: Option<usize>,
+    instruction: Option<String>,
 }
 
 impl IxAccount {
@@ -35,50 +106,106 @@ impl IxAccount {
         Self {
             name: name.to_string(),
             index: Some(index),
+            instruction: None,
         }
     }
     pub fn parsed(name: &str) -> Self {
         Self {
             name: name.to_string(),
             index: None,
+            instruction: None,
+        }
+    }
+    /// An account of the named IDL instruction, e.g. `IxAccount::idl("mintNft", "mint")`. Only instructions
+    /// decoded as that instruction are matched.
+    pub fn idl(instruction: &str, name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            index: None,
+            instruction: Some(instruction.to_string()),
         }
     }
 }
 
 /// This is the main struct used in the library and stores all the crawler data.
 pub struct Crawler {
-    client: Arc<RpcClient>,
+    source: Arc<dyn TransactionSource>,
     address: Pubkey,
     tx_filters: Vec<Box<dyn TxFilter + Send + Sync>>,
     ix_filters: Vec<Box<dyn IxFilter + Send + Sync>>,
     ix_or_filters: Vec<Box<dyn IxFilter + Send + Sync>>,
     account_indices: Vec<IxAccount>,
+    fields: Vec<IxField>,
+    extractors: Vec<Box<dyn IxExtractor + Send + Sync>>,
+    tx_extractors: Vec<Box<dyn TxExtractor + Send + Sync>>,
+    idls: HashMap<String, Idl>,
     concurrency_limit: usize,
+    batch_size: Option<usize>,
+    before: Option<Signature>,
+    until: Option<Signature>,
+    min_slot: Option<Slot>,
+    max_slot: Option<Slot>,
+    start_time: Option<UnixTimestamp>,
+    end_time: Option<UnixTimestamp>,
+    checkpoint_path: Option<PathBuf>,
+    store: Option<Arc<dyn TransactionStore>>,
+    strict: bool,
+    error_policy: ErrorPolicy,
 }
 
 impl Crawler {
-    /// Create a new Crawler object.
-    pub fn new(client: RpcClient, address: Pubkey) -> Self {
+    /// Create a new Crawler object. The source can be an `RpcClient` or any other `TransactionSource`.
+    pub fn new<S: TransactionSource + 'static>(source: S, address: Pubkey) -> Self {
         Crawler {
-            client: Arc::new(client),
+            source: Arc::new(source),
             address,
             tx_filters: Vec::new(),
             ix_filters: Vec::new(),
             ix_or_filters: Vec::new(),
             account_indices: Vec::new(),
+            fields: Vec::new(),
+            extractors: Vec::new(),
+            tx_extractors: Vec::new(),
+            idls: HashMap::new(),
             concurrency_limit: DEFAULT_CONCURRENCY_LIMIT,
+            batch_size: None,
+            before: None,
+            until: None,
+            min_slot: None,
+            max_slot: None,
+            start_time: None,
+            end_time: None,
+            checkpoint_path: None,
+            store: None,
+            strict: false,
+            error_policy: ErrorPolicy::default(),
         }
     }
 
-    pub fn new_arc_client(client: Arc<RpcClient>, address: Pubkey) -> Self {
+    pub fn new_arc_client<S: TransactionSource + 'static>(source: Arc<S>, address: Pubkey) -> Self {
         Crawler {
-            client,
+            source,
             address,
             tx_filters: Vec::new(),
             ix_filters: Vec::new(),
             ix_or_filters: Vec::new(),
             account_indices: Vec::new(),
+            fields: Vec::new(),
+            extractors: Vec::new(),
+            tx_extractors: Vec::new(),
+            idls: HashMap::new(),
             concurrency_limit: DEFAULT_CONCURRENCY_LIMIT,
+            batch_size: None,
+            before: None,
+            until: None,
+            min_slot: None,
+            max_slot: None,
+            start_time: None,
+            end_time: None,
+            checkpoint_path: None,
+            store: None,
+            strict: false,
+            error_policy: ErrorPolicy::default(),
         }
     }
 
@@ -95,6 +222,7 @@ impl Crawler {
     }
 
     /// Add an instruction filter to be applied as a logical OR to the Crawler.
+    /// For anything beyond a single OR group, combine filters with `AnyOf` or `.or()` instead.
     pub fn add_ix_or_filters<F: IxFilter + 'static + Send + Sync>(
         &mut self,
         filters: Vec<F>,
@@ -117,123 +245,373 @@ impl Crawler {
         self
     }
 
+    /// Add an instruction field to the Crawler. Fields extract typed values other than accounts from instructions,
+    /// such as amounts or the instruction type.
+    pub fn add_field(&mut self, field: IxField) -> &mut Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Add a custom extractor to the Crawler. It is called with the transaction and each instruction that passes
+    /// the instruction filters, and every `(label, value)` pair it returns is added to the results.
+    pub fn add_extractor<E: IxExtractor + 'static + Send + Sync>(
+        &mut self,
+        extractor: E,
+    ) -> &mut Self {
+        self.extractors.push(Box::new(extractor));
+        self
+    }
+
+    /// Add a transaction extractor to the Crawler. It is called once with every transaction that passes the
+    /// transaction filters, and every `(label, value)` pair it returns is added to the results.
+    pub fn add_tx_extractor<E: TxExtractor + 'static + Send + Sync>(
+        &mut self,
+        extractor: E,
+    ) -> &mut Self {
+        self.tx_extractors.push(Box::new(extractor));
+        self
+    }
+
+    /// Add the IDL of a program so its instructions can be decoded and their accounts extracted by name
+    /// with `IxAccount::idl`.
+    pub fn add_idl(&mut self, program_id: &str, idl: Idl) -> &mut Self {
+        self.idls.insert(program_id.to_string(), idl);
+        self
+    }
+
     /// Set the concurrency limit for the crawler. This is the number of concurrent requests to be made to the node.
     pub fn set_concurrency_limit(&mut self, limit: usize) -> &mut Self {
         self.concurrency_limit = limit;
         self
     }
 
-    /// Run the crawler. This will return a CrawledAccounts object or a CrawlError.
-    pub async fn run(&self) -> Result<CrawledAccounts, CrawlError> {
-        let signatures = self.get_all_signatures_for_id().await?;
+    /// Fetch transactions in batches of this many signatures per request, for sources that support it such as
+    /// JSON-RPC batch requests to a node. Transactions that fail inside a batch are retried individually, while
+    /// a batch request that fails as a whole, e.g. because the node is rate limiting, is retried with backoff.
+    /// With batching enabled the concurrency limit applies to the number of batches in flight.
+    pub fn set_batch_size(&mut self, size: usize) -> &mut Self {
+        self.batch_size = Some(size);
+        self
+    }
 
-        let transactions = self.get_transactions_from_signatures(signatures).await?;
+    /// Only crawl signatures older than this signature. Crawling starts from the newest signature by default.
+    pub fn set_before(&mut self, signature: Signature) -> &mut Self {
+        self.before = Some(signature);
+        self
+    }
 
-        let filtered_transactions: Vec<&EncodedConfirmedTransactionWithStatusMeta> = transactions
-            .iter()
-            .filter(|tx| self.tx_filters.iter().all(|filter| filter.filter(tx)))
-            .collect();
+    /// Stop crawling once this signature is reached. The signature itself is not included.
+    pub fn set_until(&mut self, signature: Signature) -> &mut Self {
+        self.until = Some(signature);
+        self
+    }
 
-        let ix_accounts = Arc::new(Mutex::new(HashMap::new()));
+    /// Skip transactions from slots lower than this one. Signature pagination stops once it is crossed.
+    pub fn set_min_slot(&mut self, slot: Slot) -> &mut Self {
+        self.min_slot = Some(slot);
+        self
+    }
 
-        filtered_transactions.par_iter().for_each(|tx| {
-            let mut instructions: Vec<&UiParsedInstruction> = match tx.transaction.transaction {
-                EncodedTransaction::Json(ref ui_tx) => match &ui_tx.message {
-                    UiMessage::Raw(_msg) => {
-                        panic!("not a parsed message");
-                    }
-                    UiMessage::Parsed(msg) => msg
-                        .instructions
-                        .iter()
-                        .map(|ix| match ix {
-                            UiInstruction::Parsed(ix) => ix,
-                            _ => panic!("not a parsed instruction"),
-                        })
-                        .collect::<Vec<&UiParsedInstruction>>(),
-                },
-                _ => panic!("Not JSON encoded transaction"),
-            };
+    /// Skip transactions from slots higher than this one.
+    pub fn set_max_slot(&mut self, slot: Slot) -> &mut Self {
+        self.max_slot = Some(slot);
+        self
+    }
 
-            // Get all inner instructions and add them to the instructions list.
-            if let Some(meta) = &tx.transaction.meta {
-                let inner_instructions = match &meta.inner_instructions {
-                    OptionSerializer::Some(item) => Some(item),
-                    _ => None,
-                };
-                if let Some(inner_instructions) = inner_instructions {
-                    let mut parsed_ixs = inner_instructions
-                        .iter()
-                        .flat_map(|ix| &ix.instructions)
-                        .map(|ix| match ix {
-                            UiInstruction::Parsed(ix) => ix,
-                            _ => panic!("not a parsed instruction"),
-                        })
-                        .collect::<Vec<&UiParsedInstruction>>();
-                    instructions.append(&mut parsed_ixs);
+    /// Only crawl transactions with a block time inside the window, given as unix timestamps in seconds.
+    /// Either end can be left open. Signature pagination stops once the start of the window is crossed.
+    pub fn set_time_window(
+        &mut self,
+        start: Option<UnixTimestamp>,
+        end: Option<UnixTimestamp>,
+    ) -> &mut Self {
+        self.start_time = start;
+        self.end_time = end;
+        self
+    }
+
+    /// Save the crawl progress to this file after every page of signatures and every batch of transactions.
+    /// The extracted records are appended to a file next to it, see `checkpoint::records_path`.
+    /// An interrupted crawl can then be continued with `resume_from`.
+    pub fn set_checkpoint_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.checkpoint_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// In strict mode the crawler returns an error if any transaction could not be fetched,
+    /// instead of listing it in the CrawlReport.
+    pub fn set_strict(&mut self, strict: bool) -> &mut Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Set how the crawler handles transactions it can't process. Defaults to skipping them and
+    /// recording them in the CrawlReport.
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) -> &mut Self {
+        self.error_policy = policy;
+        self
+    }
+
+    /// Keep fetched transactions, filter outcomes and extracted records in a store. Transactions already in the
+    /// store are read from it instead of being fetched again.
+    pub fn set_store<T: TransactionStore + 'static>(&mut self, store: T) -> &mut Self {
+        self.store = Some(Arc::new(store));
+        self
+    }
+
+    /// Run the crawler. This will return a CrawledAccounts object along with a CrawlReport, or a CrawlError.
+    pub async fn run(&self) -> Result<(CrawledAccounts, CrawlReport), CrawlError> {
+        let checkpoint = Checkpoint::new(&self.address.to_string());
+        let (_, records, report) = self
+            .crawl(checkpoint, self.checkpoint_path.as_deref(), None)
+            .await?;
+
+        Ok((crawled_accounts(&records), report))
+    }
+
+    /// Run the crawler and return every extracted value as a CrawlRecord, which keeps the signature, slot,
+    /// block time and instruction each value came from. `run` returns the same values reduced to sets.
+    pub async fn run_records(&self) -> Result<(Vec<CrawlRecord>, CrawlReport), CrawlError> {
+        let checkpoint = Checkpoint::new(&self.address.to_string());
+        let (_, records, report) = self
+            .crawl(checkpoint, self.checkpoint_path.as_deref(), None)
+            .await?;
+
+        Ok((records, report))
+    }
+
+    /// Run the crawler, writing the records of every batch of transactions to `sink` as soon as they are
+    /// extracted. Records aren't kept in memory or saved next to the checkpoint once the sink has them.
+    /// Returns the CrawlReport once the crawl is done and the sink finished.
+    pub async fn run_with_sink<K: RecordSink>(
+        &self,
+        sink: &mut K,
+    ) -> Result<CrawlReport, CrawlError> {
+        let checkpoint = Checkpoint::new(&self.address.to_string());
+        let (_, _, report) = self
+            .crawl(checkpoint, self.checkpoint_path.as_deref(), Some(sink))
+            .await?;
+
+        Ok(report)
+    }
+
+    /// Continue a crawl from the checkpoint file at `path`, skipping any signatures that were already
+    /// processed and merging the new accounts into the ones found so far. Progress keeps being saved to
+    /// the same file. If the file doesn't exist a new crawl is started.
+    pub async fn resume_from<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(CrawledAccounts, CrawlReport), CrawlError> {
+        let path = path.as_ref();
+        let address = self.address.to_string();
+
+        let checkpoint = match Checkpoint::load(path)? {
+            Some(checkpoint) => {
+                if checkpoint.address != address {
+                    return Err(CrawlError::CheckpointFailed(
+                        format!("checkpoint is for address {}", checkpoint.address),
+                        path.display().to_string(),
+                    ));
                 }
+                checkpoint
             }
+            None => Checkpoint::new(&address),
+        };
 
-            // If ix_or_filters are empty it causes the filter to fail so we use this
-            // to control when filters are applied.
-            let or_filters = self.ix_or_filters.is_empty();
+        let (_, records, report) = self.crawl(checkpoint, Some(path), None).await?;
 
-            let filtered_instructions: Vec<&UiParsedInstruction> = instructions
-                .into_iter()
-                .filter(|ix| self.ix_filters.iter().all(|filter| filter.filter(ix)))
-                .filter(|ix| {
-                    or_filters || self.ix_or_filters.iter().any(|filter| filter.filter(ix))
-                })
-                .collect();
-
-            // Fetch accounts from instructions
-            for ix in filtered_instructions {
-                for a in self.account_indices.iter() {
-                    match ix {
-                        UiParsedInstruction::PartiallyDecoded(ix) => {
-                            if let Some(index) = a.index {
-                                let address = &ix.accounts[index];
-                                let mut ix_accounts = ix_accounts.lock().unwrap();
-
-                                let ix_account = ix_accounts
-                                    .entry(a.name.to_string())
-                                    .or_insert_with(HashSet::new);
-                                ix_account.insert(address.to_string());
-                            }
-                        }
-                        UiParsedInstruction::Parsed(ix) => {
-                            if a.index.is_none() {
-                                let pointer = format!("/info/{}", a.name);
-                                let address_opt = ix.parsed.pointer(&pointer);
-                                if let Some(address) = address_opt {
-                                    let mut ix_accounts = ix_accounts.lock().unwrap();
-
-                                    let address = address.as_str().unwrap().trim_matches('\\');
-
-                                    let ix_account = ix_accounts
-                                        .entry(a.name.to_string())
-                                        .or_insert_with(HashSet::new);
-                                    ix_account.insert(address.to_string());
-                                }
-                            }
-                        }
-                    }
-                }
+        Ok((crawled_accounts(&records), report))
+    }
+
+    /// Run the crawler only over transactions newer than the newest signature in `state`, and retry the
+    /// transactions that failed to fetch in earlier runs. Returns the accounts found in the new and retried
+    /// transactions along with the updated state, which holds the merged total of accounts, the new newest
+    /// signature and the signatures that still failed, and the CrawlReport for this run.
+    pub async fn run_incremental(
+        &self,
+        state: CrawlState,
+    ) -> Result<(CrawledAccounts, CrawlState, CrawlReport), CrawlError> {
+        let mut checkpoint = Checkpoint::new(&self.address.to_string());
+        checkpoint.until = state.newest_signature.clone();
+
+        let (checkpoint, records, mut report) = self
+            .crawl(checkpoint, self.checkpoint_path.as_deref(), None)
+            .await?;
+        let mut new_accounts = crawled_accounts(&records);
+        let newest_signature = checkpoint.signatures.first().cloned();
+
+        // Earlier failures are below the `until` cursor, so the crawl above didn't list them again.
+        let retry = state
+            .failed
+            .iter()
+            .map(|sig| parse_signature(sig))
+            .collect::<Result<Vec<Signature>, CrawlError>>()?;
+        let records = self.fetch_and_extract(retry, &mut report).await?;
+        merge_accounts(&mut new_accounts, crawled_accounts(&records));
+
+        let mut accounts = state.accounts;
+        merge_accounts(&mut accounts, new_accounts.clone());
+
+        let state = CrawlState {
+            accounts,
+            newest_signature: newest_signature.or(state.newest_signature),
+            failed: report.failed.iter().map(|(sig, _)| sig.clone()).collect(),
+        };
+
+        Ok((new_accounts, state, report))
+    }
+
+    /// Stream the transactions that pass the transaction filters as they are fetched. Fetching starts while
+    /// signature pages are still being listed, and transactions arrive in the order their fetches complete.
+    /// Failed fetches are yielded as errors without ending the stream; an error listing signatures ends it.
+    pub fn stream_transactions(
+        &self,
+    ) -> impl Stream<Item = Result<(Signature, EncodedConfirmedTransactionWithStatusMeta), CrawlError>>
+           + '_ {
+        self.fetch_stream().try_filter(move |(_, tx)| {
+            future::ready(self.tx_filters.iter().all(|filter| filter.filter(tx)))
+        })
+    }
+
+    /// Stream the records extracted by the crawler as transactions are fetched, instead of collecting
+    /// them all at the end like `run_records`. Transactions that fail to fetch or can't be processed are yielded
+    /// as errors without ending the stream; an error listing signatures ends it.
+    pub fn stream(&self) -> impl Stream<Item = Result<CrawlRecord, CrawlError>> + '_ {
+        self.fetch_stream()
+            .map(move |result| {
+                let items = match result
+                    .and_then(|(signature, tx)| self.process_transaction(&signature, &tx))
+                {
+                    Ok(records) => records.into_iter().map(Ok).collect(),
+                    Err(err) => vec![Err(err)],
+                };
+                stream::iter(items)
+            })
+            .flatten()
+    }
+
+    /// Fetch the transactions inside the crawl window and append them to a JSONL cache file at `path`, without
+    /// applying any filters. Transactions already in the cache are not fetched again, and an unfinished last line
+    /// left by an interrupted run is dropped. Analyze the cache with `analyze_cache` as often as needed while
+    /// iterating on filters, without going back to the RPC node.
+    pub async fn fetch_to_cache<P: AsRef<Path>>(&self, path: P) -> Result<CrawlReport, CrawlError> {
+        let path = path.as_ref();
+
+        let cached = if path.exists() {
+            repair_cache(path)?;
+            FileSource::open(path)?
+                .transactions()
+                .iter()
+                .filter_map(transaction_signature)
+                .map(|sig| sig.to_string())
+                .collect::<HashSet<String>>()
+        } else {
+            HashSet::new()
+        };
+
+        let mut report = CrawlReport::default();
+        let mut checkpoint = Checkpoint::new(&self.address.to_string());
+        self.get_all_signatures_for_id(&mut checkpoint, None, &mut report)
+            .await?;
+
+        let pending = checkpoint
+            .signatures
+            .iter()
+            .filter(|sig| !cached.contains(*sig))
+            .map(|sig| parse_signature(sig))
+            .collect::<Result<Vec<Signature>, CrawlError>>()?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| cache_error(path, err))?;
+        let mut writer = BufWriter::new(file);
+
+        for batch in pending.chunks(CHECKPOINT_BATCH_SIZE) {
+            let transactions = self
+                .get_transactions_from_signatures(batch.to_vec(), &mut report)
+                .await;
+            self.check_strict(&report)?;
+
+            for (_, tx) in transactions.iter() {
+                serde_json::to_writer(&mut writer, tx).map_err(|err| cache_error(path, err))?;
+                writer
+                    .write_all(b"\n")
+                    .map_err(|err| cache_error(path, err))?;
             }
-        });
+            writer.flush().map_err(|err| cache_error(path, err))?;
+        }
 
-        let crawled_accounts = Arc::try_unwrap(ix_accounts).unwrap().into_inner().unwrap();
+        Ok(report)
+    }
+
+    /// Apply the crawler's filters and extraction to the transactions in a cache written by `fetch_to_cache`,
+    /// or any file or directory `FileSource` can read. Only transactions that include the crawler's address and
+    /// fall inside its slot and time window are analyzed. Returns the same results as `run`.
+    pub fn analyze_cache<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(CrawledAccounts, CrawlReport), CrawlError> {
+        let (records, report) = self.analyze_cache_records(path)?;
 
-        Ok(crawled_accounts)
+        Ok((crawled_accounts(&records), report))
+    }
+
+    /// Like `analyze_cache`, but returns every extracted value as a CrawlRecord like `run_records`.
+    pub fn analyze_cache_records<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(Vec<CrawlRecord>, CrawlReport), CrawlError> {
+        let source = FileSource::open(path)?;
+        let address = self.address.to_string();
+
+        let transactions = source
+            .transactions()
+            .iter()
+            .filter(|tx| transaction_account_keys(tx).contains(&address))
+            .filter(|tx| {
+                !self.is_below_bounds(tx.slot, tx.block_time)
+                    && !self.is_above_bounds(tx.slot, tx.block_time)
+            })
+            .filter_map(|tx| {
+                let signature = Signature::from_str(transaction_signature(tx)?).ok()?;
+                Some((signature, clone_transaction(tx)))
+            })
+            .collect::<Vec<_>>();
+
+        let mut report = CrawlReport::default();
+        let records = self.extract_records(&transactions, &mut report)?;
+
+        Ok((records, report))
+    }
+
+    /// Fetch again only the transactions that failed in a previous run and extract accounts from them.
+    /// The returned CrawlReport lists any signatures that still could not be fetched.
+    pub async fn retry_failed(
+        &self,
+        report: &CrawlReport,
+    ) -> Result<(CrawledAccounts, CrawlReport), CrawlError> {
+        let signatures = report
+            .failed
+            .iter()
+            .map(|(sig, _)| parse_signature(sig))
+            .collect::<Result<Vec<Signature>, CrawlError>>()?;
+
+        let mut new_report = CrawlReport::default();
+        let records = self.fetch_and_extract(signatures, &mut new_report).await?;
+
+        Ok((crawled_accounts(&records), new_report))
     }
 }
 
 // Associated functions for common crawl patterns
 impl Crawler {
     /// Create and run with default settings a Crawler for cmv2 mints.
-    pub async fn get_cmv2_mints(
-        client: RpcClient,
+    pub async fn get_cmv2_mints<S: TransactionSource + 'static>(
+        client: S,
         candy_machine_pubkey: Pubkey,
-    ) -> Result<CrawledAccounts, CrawlError> {
+    ) -> Result<(CrawledAccounts, CrawlReport), CrawlError> {
         Crawler::create_cmv2_mints(client, candy_machine_pubkey)
             .run()
             .await
@@ -242,7 +620,10 @@ impl Crawler {
     /// Create a crawler to get all mint and metadata accounts for a give candy machine v2 id or candy machine v2 creator.
     /// This only parses mintNFT instructions from Candy Machine V2 so is not directly equivalent to get_first_verified_creator_mints
     /// which is a more general call.
-    pub fn create_cmv2_mints(client: RpcClient, candy_machine_pubkey: Pubkey) -> Crawler {
+    pub fn create_cmv2_mints<S: TransactionSource + 'static>(
+        client: S,
+        candy_machine_pubkey: Pubkey,
+    ) -> Crawler {
         let has_program_id = TxHasProgramId::new(CMV2_PROGRAM_ID);
 
         let ix_program_id = IxProgramIdFilter::new(CMV2_PROGRAM_ID);
@@ -273,16 +654,19 @@ impl Crawler {
     }
 
     /// Create and run with default settings a Crawler for cmv2 mints.
-    pub async fn get_cmv1_mints(
-        client: RpcClient,
+    pub async fn get_cmv1_mints<S: TransactionSource + 'static>(
+        client: S,
         candy_machine_pubkey: Pubkey,
-    ) -> Result<CrawledAccounts, CrawlError> {
+    ) -> Result<(CrawledAccounts, CrawlReport), CrawlError> {
         Crawler::create_cmv1_mints(client, candy_machine_pubkey)
             .run()
             .await
     }
 
-    pub fn create_cmv1_mints(client: RpcClient, candy_machine_pubkey: Pubkey) -> Crawler {
+    pub fn create_cmv1_mints<S: TransactionSource + 'static>(
+        client: S,
+        candy_machine_pubkey: Pubkey,
+    ) -> Crawler {
         let has_program_id = TxHasProgramId::new(CMV1_PROGRAM_ID);
 
         let ix_program_id = IxProgramIdFilter::new(CMV1_PROGRAM_ID);
@@ -307,10 +691,10 @@ impl Crawler {
     }
 
     /// Create and run with default settings a Crawler for first verified creators.
-    pub async fn get_mints_by_update_authority(
-        client: RpcClient,
+    pub async fn get_mints_by_update_authority<S: TransactionSource + 'static>(
+        client: S,
         creator: Pubkey,
-    ) -> Result<CrawledAccounts, CrawlError> {
+    ) -> Result<(CrawledAccounts, CrawlReport), CrawlError> {
         Crawler::create_mints_by_update_authority(client, creator)
             .run()
             .await
@@ -319,7 +703,10 @@ impl Crawler {
     /// Create a crawler to get all mint accounts created by an update authority. This works by by finding all the
     /// `create_master_edition` and `create_master_edition_v3` instructions from calls to the token-metadata program.
     /// This is more general than get_cmv2_mints as it can find mints not created via a candy machine.
-    pub fn create_mints_by_update_authority(client: RpcClient, authority: Pubkey) -> Crawler {
+    pub fn create_mints_by_update_authority<S: TransactionSource + 'static>(
+        client: S,
+        authority: Pubkey,
+    ) -> Crawler {
         // We're looking for all the create_master_edition and create_master_edition_v3 instructions and
         // getting the mint accounts from them.
         // Creating a master edition means it's a Metaplex NFT and not a SPL token or a Fungible Asset.
@@ -352,116 +739,1315 @@ impl Crawler {
 
 // Private methods
 impl Crawler {
-    async fn get_all_signatures_for_id(&self) -> Result<Vec<Signature>, CrawlError> {
-        let mut signatures = Vec::new();
+    async fn crawl(
+        &self,
+        mut checkpoint: Checkpoint,
+        checkpoint_path: Option<&Path>,
+        mut sink: Option<&mut dyn RecordSink>,
+    ) -> Result<(Checkpoint, Vec<CrawlRecord>, CrawlReport), CrawlError> {
+        let mut report = CrawlReport::default();
+
+        // Records of the transactions processed before the crawl was interrupted. The checkpoint is saved whole
+        // once here, which also starts the log a new crawl appends to.
+        let mut all_records = Vec::new();
+        if let Some(path) = checkpoint_path {
+            let path = path.to_path_buf();
+            (checkpoint, all_records) = run_blocking(move || {
+                let records = checkpoint.load_records(&path)?;
+                checkpoint.save(&path)?;
+                Ok((checkpoint, records))
+            })
+            .await?;
+        }
+
+        if !checkpoint.listing_complete {
+            self.get_all_signatures_for_id(&mut checkpoint, checkpoint_path, &mut report)
+                .await?;
+        }
+
+        let pending = checkpoint
+            .signatures
+            .iter()
+            .filter(|sig| !checkpoint.processed.contains(*sig))
+            .map(|sig| parse_signature(sig))
+            .collect::<Result<Vec<Signature>, CrawlError>>()?;
+
+        for batch in pending.chunks(CHECKPOINT_BATCH_SIZE) {
+            let transactions = self.fetch_batch(batch, &mut report).await?;
+            self.check_strict(&report)?;
+
+            let mut records = self.extract_records(&transactions, &mut report)?;
+            if let Some(store) = self.store.clone() {
+                let outcomes = transactions
+                    .iter()
+                    .map(|(sig, tx)| {
+                        let passed = self.tx_filters.iter().all(|filter| filter.filter(tx));
+                        (sig.to_string(), passed)
+                    })
+                    .collect::<Vec<_>>();
+                records = run_blocking(move || {
+                    store.save_results(&outcomes, &records)?;
+                    Ok(records)
+                })
+                .await?;
+            }
+            // Records handed to a sink aren't kept. Otherwise they're saved before the checkpoint so every
+            // processed transaction has its records on disk.
+            match sink.as_mut() {
+                Some(sink) => sink.write(&records)?,
+                None => {
+                    if let Some(path) = checkpoint_path {
+                        let path = path.to_path_buf();
+                        records = run_blocking(move || {
+                            Checkpoint::append_records(&path, &records)?;
+                            Ok(records)
+                        })
+                        .await?;
+                    }
+                    all_records.append(&mut records);
+                }
+            }
 
-        // Initial config
-        let mut before = None;
-        let until = None;
-        let limit = Some(1000);
-        let commitment = Some(CommitmentConfig::finalized());
-        let mut retries = 0u8;
+            let entry = CheckpointEntry::Processed {
+                signatures: transactions
+                    .iter()
+                    .map(|(sig, _)| sig.to_string())
+                    .collect(),
+            };
+            append_checkpoint(&mut checkpoint, checkpoint_path, entry).await?;
+        }
+
+        if let Some(sink) = sink {
+            sink.finish()?;
+        }
+
+        Ok((checkpoint, all_records, report))
+    }
+
+    /// Get the transactions for a batch of signatures, reading the ones already in the store from it and saving
+    /// the ones that had to be fetched.
+    async fn fetch_batch(
+        &self,
+        batch: &[Signature],
+        report: &mut CrawlReport,
+    ) -> Result<Vec<(Signature, EncodedConfirmedTransactionWithStatusMeta)>, CrawlError> {
+        let store = match &self.store {
+            Some(store) => store.clone(),
+            None => {
+                return Ok(self
+                    .get_transactions_from_signatures(batch.to_vec(), report)
+                    .await)
+            }
+        };
+
+        let mut transactions = {
+            let store = store.clone();
+            let batch = batch.to_vec();
+            run_blocking(move || store.load_transactions(&batch)).await?
+        };
+        let stored = transactions
+            .iter()
+            .map(|(sig, _)| *sig)
+            .collect::<HashSet<Signature>>();
+        let missing = batch
+            .iter()
+            .filter(|sig| !stored.contains(*sig))
+            .copied()
+            .collect::<Vec<Signature>>();
+
+        let fetched = self.get_transactions_from_signatures(missing, report).await;
+        let fetched = run_blocking(move || {
+            store.save_transactions(&fetched)?;
+            Ok(fetched)
+        })
+        .await?;
+        transactions.extend(fetched);
+
+        Ok(transactions)
+    }
+
+    async fn get_all_signatures_for_id(
+        &self,
+        checkpoint: &mut Checkpoint,
+        checkpoint_path: Option<&Path>,
+        report: &mut CrawlReport,
+    ) -> Result<(), CrawlError> {
+        let mut before = match &checkpoint.before {
+            Some(sig) => Some(parse_signature(sig)?),
+            None => self.before,
+        };
+        let until = match &checkpoint.until {
+            Some(sig) => Some(parse_signature(sig)?),
+            None => self.until,
+        };
 
         loop {
+            let page = self.get_signature_page(before, until).await?;
+            report.retries += page.retries;
+            let entry = CheckpointEntry::Listed {
+                signatures: page.signatures,
+                before: page.next.map(|next| next.to_string()),
+            };
+            append_checkpoint(checkpoint, checkpoint_path, entry).await?;
+
+            // Loop until we reach the last batch of signatures or leave the crawl window.
+            match page.next {
+                Some(next) => before = Some(next),
+                None => break,
+            }
+        }
+
+        append_checkpoint(
+            checkpoint,
+            checkpoint_path,
+            CheckpointEntry::ListingComplete,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stream the signatures inside the crawl window, newest first, one page at a time.
+    fn signature_stream(&self) -> impl Stream<Item = Result<Signature, CrawlError>> + '_ {
+        // The state is the `before` cursor of the next page, or None once the last page has been listed.
+        stream::try_unfold(Some(self.before), move |cursor| async move {
+            let before = match cursor {
+                Some(before) => before,
+                None => return Ok(None),
+            };
+            let page = self.get_signature_page(before, self.until).await?;
+            Ok(Some((page.signatures, page.next.map(Some))))
+        })
+        .map_ok(|signatures| stream::iter(signatures.into_iter().map(|sig| parse_signature(&sig))))
+        .try_flatten()
+    }
+
+    /// Stream fetched transactions, starting fetches while signature pages are still being listed.
+    fn fetch_stream(
+        &self,
+    ) -> impl Stream<Item = Result<(Signature, EncodedConfirmedTransactionWithStatusMeta), CrawlError>>
+           + '_ {
+        self.signature_stream()
+            .map(move |signature| {
+                let source = self.source.clone();
+                async move {
+                    let signature = signature?;
+                    let (result, _retries) = get_transaction(source, signature).await;
+                    result.map(|tx| (signature, tx))
+                }
+            })
+            .buffer_unordered(self.concurrency_limit)
+    }
+
+    async fn get_signature_page(
+        &self,
+        before: Option<Signature>,
+        until: Option<Signature>,
+    ) -> Result<SignaturePage, CrawlError> {
+        let (sigs, retries) = with_retries(self.source.backoff(), || {
             let config = GetConfirmedSignaturesForAddress2Config {
                 before,
                 until,
-                limit,
-                commitment,
-            };
-            let sigs = self
-                .client
-                .get_signatures_for_address_with_config(&self.address, config)
-                .map_err(|err| {
-                    CrawlError::ClientError(err.to_string(), self.address.to_string())
-                })?;
-
-            let last_sig = match sigs.last() {
-                Some(sig) => sig,
-                None => break,
+                limit: Some(SIGNATURES_PAGE_LIMIT),
+                commitment: Some(CommitmentConfig::finalized()),
             };
+            self.source
+                .get_signatures_for_address(&self.address, config)
+        })
+        .await;
+        let sigs = sigs?;
+
+        let page_len = sigs.len();
+        let last_sig = match sigs.last() {
+            Some(sig) => Some(parse_signature(&sig.signature)?),
+            None => None,
+        };
+
+        let mut signatures = Vec::new();
+
+        // Signatures are returned newest first, so once one falls below the lower bound
+        // every following signature will as well.
+        let mut crossed_lower_bound = false;
+        for sig in sigs {
+            if self.is_below_bounds(sig.slot, sig.block_time) {
+                crossed_lower_bound = true;
+                break;
+            }
+            if self.is_above_bounds(sig.slot, sig.block_time) {
+                continue;
+            }
+            signatures.push(sig.signature);
+        }
 
-            let last_sig = Signature::from_str(&last_sig.signature)
-                .map_err(|err| CrawlError::SignatureParseFailed(err.to_string()))?;
+        let next = if crossed_lower_bound || page_len < SIGNATURES_PAGE_LIMIT {
+            None
+        } else {
+            last_sig
+        };
+
+        Ok(SignaturePage {
+            signatures,
+            next,
+            retries,
+        })
+    }
 
-            // Loop until we reach the last batch of signatures.
-            match sigs.len() {
-                1000 => {
-                    before = Some(last_sig);
-                    signatures.extend(sigs);
-                    retries = 0;
+    fn is_below_bounds(&self, slot: Slot, block_time: Option<UnixTimestamp>) -> bool {
+        let below_slot = self.min_slot.map(|min| slot < min).unwrap_or(false);
+        let below_time = match (self.start_time, block_time) {
+            (Some(start), Some(time)) => time < start,
+            _ => false,
+        };
+        below_slot || below_time
+    }
+
+    fn is_above_bounds(&self, slot: Slot, block_time: Option<UnixTimestamp>) -> bool {
+        let above_slot = self.max_slot.map(|max| slot > max).unwrap_or(false);
+        let above_time = match (self.end_time, block_time) {
+            (Some(end), Some(time)) => time > end,
+            _ => false,
+        };
+        above_slot || above_time
+    }
+
+    fn extract_records(
+        &self,
+        transactions: &[(Signature, EncodedConfirmedTransactionWithStatusMeta)],
+        report: &mut CrawlReport,
+    ) -> Result<Vec<CrawlRecord>, CrawlError> {
+        let results = transactions
+            .par_iter()
+            .map(|(signature, tx)| (signature, self.process_transaction(signature, tx)))
+            .collect::<Vec<_>>();
+
+        let mut records = Vec::new();
+
+        for (signature, result) in results {
+            match result {
+                Ok(mut tx_records) => records.append(&mut tx_records),
+                Err(err) => match self.error_policy {
+                    ErrorPolicy::Abort => return Err(err),
+                    ErrorPolicy::Skip => report.skipped.push((signature.to_string(), err)),
+                },
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Apply the filters to a single transaction and return the records extracted from it.
+    fn process_transaction(
+        &self,
+        signature: &Signature,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<Vec<CrawlRecord>, CrawlError> {
+        let ui_tx = match &tx.transaction.transaction {
+            EncodedTransaction::Json(ui_tx) => ui_tx,
+            other => {
+                return Err(CrawlError::UnsupportedEncoding(
+                    encoding_name(other).to_string(),
+                    signature.to_string(),
+                ))
+            }
+        };
+
+        if !self.tx_filters.iter().all(|filter| filter.filter(tx)) {
+            return Ok(Vec::new());
+        }
+
+        // Versioned transactions can load accounts from lookup tables, which compiled instructions index into.
+        let account_keys = transaction_account_keys(tx);
+        let resolve = |ix| {
+            resolve_instruction(ix, &account_keys)
+                .ok_or_else(|| CrawlError::UnparsedInstruction(signature.to_string()))
+        };
+
+        let mut instructions: Vec<CrawledInstruction> = match &ui_tx.message {
+            UiMessage::Raw(msg) => msg
+                .instructions
+                .iter()
+                .enumerate()
+                .map(|(index, ix)| {
+                    resolve_compiled_instruction(ix, &account_keys)
+                        .map(|ix| CrawledInstruction::outer(index, Cow::Owned(ix)))
+                        .ok_or_else(|| CrawlError::UnparsedInstruction(signature.to_string()))
+                })
+                .collect::<Result<Vec<CrawledInstruction>, CrawlError>>()?,
+            UiMessage::Parsed(msg) => msg
+                .instructions
+                .iter()
+                .enumerate()
+                .map(|(index, ix)| resolve(ix).map(|ix| CrawledInstruction::outer(index, ix)))
+                .collect::<Result<Vec<CrawledInstruction>, CrawlError>>()?,
+        };
+
+        let outer_count = instructions.len();
+        let outer_program_ids = instructions
+            .iter()
+            .map(|ix| instruction_program_id(&ix.ix).to_string())
+            .collect::<Vec<_>>();
+
+        // Get all inner instructions and add them to the instructions list.
+        if let Some(meta) = &tx.transaction.meta {
+            let inner_instructions = match &meta.inner_instructions {
+                OptionSerializer::Some(item) => Some(item),
+                _ => None,
+            };
+            if let Some(inner_instructions) = inner_instructions {
+                let stack_heights = inner_stack_heights(tx, &outer_program_ids);
+
+                for inner in inner_instructions {
+                    let index = inner.index as usize;
+                    let heights = stack_heights
+                        .get(index)
+                        .and_then(|heights| heights.as_ref())
+                        .filter(|heights| heights.len() == inner.instructions.len());
+
+                    // Program ids on the invocation stack, starting with the top-level instruction's program.
+                    // Without stack heights every inner instruction is attributed to the top-level program.
+                    let mut stack = outer_program_ids
+                        .get(index)
+                        .cloned()
+                        .into_iter()
+                        .collect::<Vec<_>>();
+
+                    for (i, ix) in inner.instructions.iter().enumerate() {
+                        let ix = resolve(ix)?;
+                        let stack_height = heights.map(|heights| heights[i]);
+                        if let Some(height) = stack_height {
+                            stack.truncate(height.saturating_sub(1) as usize);
+                        }
+                        let parent_program_id = stack.last().cloned();
+                        if stack_height.is_some() {
+                            stack.push(instruction_program_id(&ix).to_string());
+                        }
+
+                        instructions.push(CrawledInstruction {
+                            index,
+                            inner_index: Some(i),
+                            stack_height,
+                            parent_program_id,
+                            ix,
+                        });
+                    }
                 }
-                0 => {
-                    if retries < 10 {
-                        retries += 1;
-                        continue;
-                    } else {
-                        break;
+            }
+        }
+
+        // If ix_or_filters are empty it causes the filter to fail so we use this
+        // to control when filters are applied.
+        let or_filters = self.ix_or_filters.is_empty();
+
+        let filtered_instructions = instructions
+            .iter()
+            .filter(|ix| {
+                let ctx = ix.context(&instructions[..outer_count]);
+                self.ix_filters
+                    .iter()
+                    .all(|filter| filter.filter_with_context(&ctx))
+                    && (or_filters
+                        || self
+                            .ix_or_filters
+                            .iter()
+                            .any(|filter| filter.filter_with_context(&ctx)))
+            })
+            .collect::<Vec<_>>();
+
+        let mut records = Vec::new();
+
+        // Fetch accounts from instructions
+        for ix in filtered_instructions {
+            let mut record = |label: &str, value: Value| {
+                records.push(CrawlRecord {
+                    label: label.to_string(),
+                    value,
+                    signature: signature.to_string(),
+                    slot: tx.slot,
+                    block_time: tx.block_time,
+                    instruction_index: Some(ix.index),
+                    inner_instruction_index: ix.inner_index,
+                    program_id: Some(instruction_program_id(&ix.ix).to_string()),
+                })
+            };
+
+            // Instructions of programs with an IDL are decoded so their accounts can be looked up by name.
+            let decoded = match ix.ix.as_ref() {
+                UiParsedInstruction::PartiallyDecoded(ix) => self
+                    .idls
+                    .get(&ix.program_id)
+                    .and_then(|idl| idl.decode_instruction(ix)),
+                UiParsedInstruction::Parsed(_) => None,
+            };
+
+            for a in self.account_indices.iter() {
+                match (&a.instruction, ix.ix.as_ref()) {
+                    (Some(instruction), ui_ix) => {
+                        let parsed = match ui_ix {
+                            UiParsedInstruction::Parsed(ix) => Some(ix),
+                            UiParsedInstruction::PartiallyDecoded(_) => decoded.as_ref(),
+                        };
+                        let address_opt = parsed
+                            .filter(|ix| ix.parsed.get("type") == Some(&json!(instruction)))
+                            .and_then(|ix| parsed_account(&ix.parsed, &a.name));
+                        if let Some(address) = address_opt {
+                            record(&a.name, json!(address));
+                        }
+                    }
+                    (None, UiParsedInstruction::PartiallyDecoded(ix)) => {
+                        let address_opt = match a.index {
+                            Some(index) => ix.accounts.get(index).map(String::as_str),
+                            None => decoded
+                                .as_ref()
+                                .and_then(|ix| parsed_account(&ix.parsed, &a.name)),
+                        };
+                        if let Some(address) = address_opt {
+                            record(&a.name, json!(address));
+                        }
+                    }
+                    (None, UiParsedInstruction::Parsed(ix)) => {
+                        if a.index.is_none() {
+                            if let Some(address) = parsed_account(&ix.parsed, &a.name) {
+                                record(&a.name, json!(address));
+                            }
+                        }
                     }
                 }
-                _ => {
-                    signatures.extend(sigs);
-                    break;
+            }
+
+            for field in self.fields.iter() {
+                if let Some(value) = field.extract(&ix.ix, decoded.as_ref()) {
+                    record(field.label(), value);
+                }
+            }
+
+            for extractor in self.extractors.iter() {
+                for (label, value) in extractor.extract(tx, &ix.ix) {
+                    record(&label, value);
                 }
             }
         }
 
-        let signatures = signatures
-            .into_iter()
-            .map(|sig| sig.signature)
-            .map(|s| Signature::from_str(&s).unwrap())
-            .collect();
+        for extractor in self.tx_extractors.iter() {
+            for (label, value) in extractor.extract(tx) {
+                records.push(CrawlRecord {
+                    label,
+                    value,
+                    signature: signature.to_string(),
+                    slot: tx.slot,
+                    block_time: tx.block_time,
+                    instruction_index: None,
+                    inner_instruction_index: None,
+                    program_id: None,
+                });
+            }
+        }
 
-        Ok(signatures)
+        Ok(records)
     }
 
     async fn get_transactions_from_signatures(
         &self,
         signatures: Vec<Signature>,
-    ) -> Result<Vec<EncodedConfirmedTransactionWithStatusMeta>, CrawlError> {
+        report: &mut CrawlReport,
+    ) -> Vec<(Signature, EncodedConfirmedTransactionWithStatusMeta)> {
         let mut transactions = Vec::new();
-        let mut errors = Vec::new();
 
         let mut tx_tasks = Vec::new();
 
         // Create a Semaphore to limit the number of concurrent requests.
         let sem = Arc::new(Semaphore::new(self.concurrency_limit));
 
-        for signature in signatures {
+        let batch_size = self.batch_size.unwrap_or(1).max(1);
+
+        for batch in signatures.chunks(batch_size) {
             let permit = Arc::clone(&sem).acquire_owned().await.unwrap();
-            let client = self.client.clone();
+            let source = self.source.clone();
+            let batch = batch.to_vec();
             tx_tasks.push(tokio::spawn(async move {
                 // Move permit into the closure so it is dropped when the task is dropped.
                 let _permit = permit;
-                get_transaction(client, signature).await
+                get_transactions(source, batch).await
             }));
         }
 
         for task in tx_tasks {
-            let res = task.await.unwrap();
-            if let Ok(tx) = res {
-                transactions.push(tx);
-            } else {
-                errors.push(res.unwrap_err());
+            let (fetched, batch_retries) = task.await.unwrap();
+            report.retries += batch_retries;
+            for (signature, (res, retries)) in fetched {
+                report.retries += retries;
+                match res {
+                    Ok(tx) => transactions.push((signature, tx)),
+                    Err(err) => report.failed.push((signature.to_string(), err)),
+                }
             }
         }
 
-        // TODO: add logging for errors
+        transactions
+    }
 
-        Ok(transactions)
+    /// Fetch the transactions for a list of signatures outside of a crawl and extract their records.
+    async fn fetch_and_extract(
+        &self,
+        signatures: Vec<Signature>,
+        report: &mut CrawlReport,
+    ) -> Result<Vec<CrawlRecord>, CrawlError> {
+        let transactions = self
+            .get_transactions_from_signatures(signatures, report)
+            .await;
+        self.check_strict(report)?;
+
+        self.extract_records(&transactions, report)
+    }
+
+    fn check_strict(&self, report: &CrawlReport) -> Result<(), CrawlError> {
+        if self.strict && !report.is_complete() {
+            return Err(CrawlError::IncompleteCrawl(report.failed.len()));
+        }
+        Ok(())
+    }
+}
+
+/// An instruction from a transaction along with its position in it.
+struct CrawledInstruction<'a> {
+    index: usize,
+    inner_index: Option<usize>,
+    stack_height: Option<u32>,
+    parent_program_id: Option<String>,
+    ix: Cow<'a, UiParsedInstruction>,
+}
+
+impl<'a> CrawledInstruction<'a> {
+    fn outer(index: usize, ix: Cow<'a, UiParsedInstruction>) -> Self {
+        Self {
+            index,
+            inner_index: None,
+            stack_height: Some(1),
+            parent_program_id: None,
+            ix,
+        }
+    }
+
+    /// The filter context for this instruction, given the transaction's top-level instructions.
+    fn context<'b>(&'b self, outer: &'b [CrawledInstruction]) -> IxContext<'b> {
+        IxContext {
+            instruction: &self.ix,
+            index: self.index,
+            inner_index: self.inner_index,
+            stack_height: self.stack_height,
+            parent_program_id: self.parent_program_id.as_deref(),
+            outer_instruction: self
+                .inner_index
+                .and_then(|_| outer.get(self.index))
+                .map(|outer| outer.ix.as_ref()),
+        }
+    }
+}
+
+/// A page of signatures inside the crawl window, along with the cursor for the next page if there is one.
+struct SignaturePage {
+    signatures: Vec<String>,
+    next: Option<Signature>,
+    retries: usize,
+}
+
+/// The result of fetching a transaction along with the number of retries it took.
+type FetchResult = (
+    Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>,
+    usize,
+);
+
+/// Fetch a transaction, returning the result along with the number of retries it took.
+async fn get_transaction(source: Arc<dyn TransactionSource>, signature: Signature) -> FetchResult {
+    with_retries(source.backoff(), || source.get_transaction(&signature)).await
+}
+
+/// Fetch a batch of transactions in a single request, retrying any that failed individually. While the request as a
+/// whole fails, e.g. because the node is rate limiting, the batch is retried with backoff instead, and once those
+/// retries run out or the node rejects the batch outright, each signature is fetched with `get_transaction`. Batches
+/// of a single signature are fetched with `get_transaction` too. Returns the results along with the number of batch
+/// retries.
+async fn get_transactions(
+    source: Arc<dyn TransactionSource>,
+    signatures: Vec<Signature>,
+) -> (Vec<(Signature, FetchResult)>, usize) {
+    if signatures.len() == 1 {
+        let signature = signatures[0];
+        return (
+            vec![(signature, get_transaction(source, signature).await)],
+            0,
+        );
+    }
+
+    let (results, batch_retries) =
+        with_retries(source.backoff(), || get_batch(source.as_ref(), &signatures)).await;
+    let results = match results {
+        Ok(results) => results,
+        Err(_) => {
+            let mut fetched = Vec::with_capacity(signatures.len());
+            for signature in signatures {
+                fetched.push((signature, get_transaction(source.clone(), signature).await));
+            }
+            return (fetched, batch_retries);
+        }
+    };
+
+    let mut results = results.into_iter();
+    let mut fetched = Vec::with_capacity(signatures.len());
+    for signature in signatures {
+        let result = match results.next() {
+            Some(Ok(tx)) => (Ok(tx), 0),
+            Some(Err(err)) if !err.is_retryable() => (Err(err), 0),
+            Some(Err(_)) => {
+                let (result, retries) = get_transaction(source.clone(), signature).await;
+                (result, retries + 1)
+            }
+            None => (
+                Err(CrawlError::SourceFailed(
+                    "no result returned for signature".to_string(),
+                    signature.to_string(),
+                )),
+                0,
+            ),
+        };
+        fetched.push((signature, result));
+    }
+
+    (fetched, batch_retries)
+}
+
+/// Fetch a batch of transactions, failing as a whole if the source reports that the batch request failed or was
+/// rejected.
+async fn get_batch(
+    source: &dyn TransactionSource,
+    signatures: &[Signature],
+) -> Result<Vec<Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>>, CrawlError> {
+    let results = source.get_transactions(signatures).await;
+
+    let batch_error = results.iter().find_map(|result| match result {
+        Err(err @ (CrawlError::BatchFailed(_) | CrawlError::BatchRejected(_))) => Some(err.clone()),
+        _ => None,
+    });
+
+    match batch_error {
+        Some(err) => Err(err),
+        None => Ok(results),
+    }
+}
+
+/// Run a request until it succeeds or runs out of retries, backing off exponentially between attempts
+/// unless `backoff` is false. Returns the last result along with the number of retries used.
+async fn with_retries<T, F, Fut>(backoff: bool, mut request: F) -> (Result<T, CrawlError>, usize)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, CrawlError>>,
+{
+    let mut retries = 0usize;
+    let mut delay = RETRY_BASE_DELAY_MS;
+
+    loop {
+        let result = request().await;
+        match result {
+            // Retry because occasionally Google Big Table returns empty values, apparently.
+            // Missing transactions from offline sources won't show up on a later attempt, so those aren't retried.
+            Err(err) if !err.is_retryable() => return (Err(err), retries),
+            Err(_) if retries < MAX_RETRIES => {
+                retries += 1;
+                if backoff {
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                    delay = (delay * 2).min(RETRY_MAX_DELAY_MS);
+                }
+            }
+            _ => return (result, retries),
+        }
+    }
+}
+
+/// Turn an instruction into a parsed one, resolving the account indices of compiled instructions.
+fn resolve_instruction<'a>(
+    ix: &'a UiInstruction,
+    account_keys: &[String],
+) -> Option<Cow<'a, UiParsedInstruction>> {
+    match ix {
+        UiInstruction::Parsed(ix) => Some(Cow::Borrowed(ix)),
+        UiInstruction::Compiled(ix) => {
+            resolve_compiled_instruction(ix, account_keys).map(Cow::Owned)
+        }
+    }
+}
+
+/// Resolve the program id and account indices of a compiled instruction against the transaction's account keys,
+/// returning None if any index is out of range.
+fn resolve_compiled_instruction(
+    ix: &UiCompiledInstruction,
+    account_keys: &[String],
+) -> Option<UiParsedInstruction> {
+    let program_id = account_keys.get(ix.program_id_index as usize)?.clone();
+    let accounts = ix
+        .accounts
+        .iter()
+        .map(|index| account_keys.get(*index as usize).cloned())
+        .collect::<Option<Vec<String>>>()?;
+
+    Some(UiParsedInstruction::PartiallyDecoded(
+        UiPartiallyDecodedInstruction {
+            program_id,
+            accounts,
+            data: ix.data.clone(),
+        },
+    ))
+}
+
+fn encoding_name(tx: &EncodedTransaction) -> &'static str {
+    match tx {
+        EncodedTransaction::LegacyBinary(_) => "legacy binary",
+        EncodedTransaction::Binary(_, TransactionBinaryEncoding::Base58) => "base58",
+        EncodedTransaction::Binary(_, TransactionBinaryEncoding::Base64) => "base64",
+        EncodedTransaction::Json(_) => "json",
+        EncodedTransaction::Accounts(_) => "accounts",
     }
 }
 
-async fn get_transaction(
-    client: Arc<RpcClient>,
-    signature: Signature,
-) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
-    // Retry because occasionally Google Big Table returns empty values, apparently.
-    let result = retry(Fixed::from_millis(500).take(10), || {
-        client.get_transaction(&signature, UiTransactionEncoding::JsonParsed)
+fn parse_signature(signature: &str) -> Result<Signature, CrawlError> {
+    Signature::from_str(signature).map_err(|err| CrawlError::SignatureParseFailed(err.to_string()))
+}
+
+/// Recover the stack height of every inner instruction from the "Program <id> invoke [<height>]" log lines,
+/// grouped by the top-level instruction that invoked them. A group is None when the logs don't cover it.
+fn inner_stack_heights(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+    outer_program_ids: &[String],
+) -> Vec<Option<Vec<u32>>> {
+    let mut groups = vec![None; outer_program_ids.len()];
+
+    let invocations = log_messages(tx).iter().filter_map(|line| {
+        let (program_id, height) = line.strip_prefix("Program ")?.split_once(" invoke [")?;
+        let height = height.strip_suffix(']')?.parse::<u32>().ok()?;
+        Some((program_id, height))
     });
-    let transaction =
-        result.map_err(|err| CrawlError::ClientError(err.to_string(), signature.to_string()))?;
 
-    Ok(transaction)
+    let mut current = None;
+    let mut next_outer = 0;
+
+    for (program_id, height) in invocations {
+        if height == 1 {
+            // Precompiled programs don't log their invocation, so skip top-level instructions that don't match.
+            while next_outer < outer_program_ids.len()
+                && outer_program_ids[next_outer] != program_id
+            {
+                next_outer += 1;
+            }
+            if next_outer == outer_program_ids.len() {
+                break;
+            }
+            groups[next_outer] = Some(Vec::new());
+            current = Some(next_outer);
+            next_outer += 1;
+        } else if let Some(group) = current.and_then(|i| groups[i].as_mut()) {
+            group.push(height);
+        }
+    }
+
+    groups
+}
+
+/// Look up an account by name in the "info" of a parsed instruction.
+fn parsed_account<'a>(parsed: &'a Value, name: &str) -> Option<&'a str> {
+    let pointer = format!("/info/{}", name);
+    parsed
+        .pointer(&pointer)
+        .and_then(|a| a.as_str())
+        .map(|address| address.trim_matches('\\'))
+}
+
+fn merge_accounts(accounts: &mut CrawledAccounts, other: CrawledAccounts) {
+    for (label, addresses) in other {
+        accounts.entry(label).or_default().extend(addresses);
+    }
+}
+
+fn cache_error<E: ToString>(path: &Path, err: E) -> CrawlError {
+    CrawlError::CacheFailed(err.to_string(), path.display().to_string())
+}
+
+/// Make sure appending to a cache starts on a new line. An unfinished last line left by an interrupted write is cut
+/// off, while a complete transaction that's only missing its newline gets one.
+fn repair_cache(path: &Path) -> Result<(), CrawlError> {
+    let data = fs::read(path).map_err(|err| cache_error(path, err))?;
+    let end = data
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let last_line = &data[end..];
+
+    if last_line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(());
+    }
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(path)
+        .map_err(|err| cache_error(path, err))?;
+    if serde_json::from_slice::<EncodedConfirmedTransactionWithStatusMeta>(last_line).is_ok() {
+        file.write_all(b"\n")
+    } else {
+        file.set_len(end as u64)
+    }
+    .map_err(|err| cache_error(path, err))
+}
+
+/// Apply an entry to the checkpoint, appending it to the checkpoint file first if there is one.
+async fn append_checkpoint(
+    checkpoint: &mut Checkpoint,
+    path: Option<&Path>,
+    entry: CheckpointEntry,
+) -> Result<(), CrawlError> {
+    let entry = match path {
+        Some(path) => {
+            let path = path.to_path_buf();
+            run_blocking(move || {
+                Checkpoint::append(&path, &entry)?;
+                Ok(entry)
+            })
+            .await?
+        }
+        None => entry,
+    };
+    checkpoint.apply(entry);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sources::MemorySource,
+        test_utils::{transaction, TestIx},
+    };
+    use std::sync::Mutex;
+
+    const A: &str = "ProgramA111111111111111111111111111111111111";
+    const B: &str = "ProgramB111111111111111111111111111111111111";
+    const C: &str = "ProgramC111111111111111111111111111111111111";
+    const D: &str = "ProgramD111111111111111111111111111111111111";
+    const ED25519: &str = "Ed25519SigVerify111111111111111111111111111";
+
+    type Position = (Option<usize>, Option<u32>, Option<String>);
+
+    /// Passes every instruction, recording its inner index, stack height and parent program.
+    #[derive(Clone, Default)]
+    struct ContextLog(Arc<Mutex<Vec<Position>>>);
+
+    impl IxFilter for ContextLog {
+        fn filter(&self, _ix: &UiParsedInstruction) -> bool {
+            true
+        }
+
+        fn filter_with_context(&self, ctx: &IxContext) -> bool {
+            self.0.lock().unwrap().push((
+                ctx.inner_index,
+                ctx.stack_height,
+                ctx.parent_program_id.map(|id| id.to_string()),
+            ));
+            true
+        }
+    }
+
+    fn positions(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<Position> {
+        let log = ContextLog::default();
+        let mut crawler = Crawler::new(MemorySource::new(Vec::new()), Pubkey::new_unique());
+        crawler.add_ix_filter(log.clone());
+        crawler
+            .process_transaction(&Signature::default(), tx)
+            .unwrap();
+
+        let positions = log.0.lock().unwrap().clone();
+        positions
+    }
+
+    fn program_ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn nested_cpi_stack_heights() {
+        let tx = transaction(
+            "sig",
+            1,
+            &[A, B, C, D],
+            &[(A, &[])],
+            &[(0, &[(B, &[]), (C, &[]), (D, &[])])],
+            &[
+                &format!("Program {} invoke [1]", A),
+                &format!("Program {} invoke [2]", B),
+                &format!("Program {} invoke [3]", C),
+                &format!("Program {} success", C),
+                &format!("Program {} success", B),
+                &format!("Program {} invoke [2]", D),
+                &format!("Program {} success", D),
+                &format!("Program {} success", A),
+            ],
+        );
+
+        assert_eq!(
+            inner_stack_heights(&tx, &program_ids(&[A])),
+            vec![Some(vec![2, 3, 2])]
+        );
+        assert_eq!(
+            positions(&tx),
+            vec![
+                (None, Some(1), None),
+                (Some(0), Some(2), Some(A.to_string())),
+                (Some(1), Some(3), Some(B.to_string())),
+                (Some(2), Some(2), Some(A.to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn precompile_top_level_instructions_are_skipped() {
+        let tx = transaction(
+            "sig",
+            1,
+            &[ED25519, A, B],
+            &[(ED25519, &[]), (A, &[])],
+            &[(1, &[(B, &[])])],
+            &[
+                &format!("Program {} invoke [1]", A),
+                &format!("Program {} invoke [2]", B),
+                &format!("Program {} success", B),
+                &format!("Program {} success", A),
+            ],
+        );
+
+        assert_eq!(
+            inner_stack_heights(&tx, &program_ids(&[ED25519, A])),
+            vec![None, Some(vec![2])]
+        );
+        assert_eq!(positions(&tx)[2], (Some(0), Some(2), Some(A.to_string())));
+    }
+
+    #[test]
+    fn truncated_logs_fall_back_to_no_stack_height() {
+        let tx = transaction(
+            "sig",
+            1,
+            &[A, B, C, D],
+            &[(A, &[]), (D, &[])],
+            &[(0, &[(B, &[]), (C, &[])]), (1, &[(B, &[])])],
+            &[
+                &format!("Program {} invoke [1]", A),
+                &format!("Program {} invoke [2]", B),
+                "Log truncated",
+            ],
+        );
+
+        assert_eq!(
+            inner_stack_heights(&tx, &program_ids(&[A, D])),
+            vec![Some(vec![2]), None]
+        );
+        // Without stack heights inner instructions are attributed to their top-level instruction's program.
+        assert_eq!(
+            positions(&tx)[2..],
+            [
+                (Some(0), None, Some(A.to_string())),
+                (Some(1), None, Some(A.to_string())),
+                (Some(0), None, Some(D.to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_v0_instructions_resolve_loaded_addresses() {
+        const PAYER: &str = "Payer111111111111111111111111111111111111111";
+        const WRITABLE: &str = "Writab1e11111111111111111111111111111111111";
+        const LOOKUP_TABLE: &str = "LookupTab1e11111111111111111111111111111111";
+
+        // The program and one of the accounts are loaded from a lookup table rather than listed statically.
+        let tx: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(json!({
+            "slot": 1,
+            "blockTime": null,
+            "version": 0,
+            "transaction": {
+                "signatures": ["sig"],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 1,
+                    },
+                    "accountKeys": [PAYER, A],
+                    "recentBlockhash": "11111111111111111111111111111111",
+                    "instructions": [{ "programIdIndex": 3, "accounts": [2, 0, 1], "data": "" }],
+                    "addressTableLookups": [
+                        { "accountKey": LOOKUP_TABLE, "writableIndexes": [0], "readonlyIndexes": [1] },
+                    ],
+                },
+            },
+            "meta": {
+                "err": null,
+                "status": { "Ok": null },
+                "fee": 5000,
+                "preBalances": [],
+                "postBalances": [],
+                "innerInstructions": [],
+                "logMessages": [],
+                "preTokenBalances": [],
+                "postTokenBalances": [],
+                "loadedAddresses": { "writable": [WRITABLE], "readonly": [B] },
+            },
+        }))
+        .unwrap();
+
+        // Static keys come first, then the writable and then the readonly loaded addresses.
+        assert_eq!(
+            transaction_account_keys(&tx),
+            program_ids(&[PAYER, A, WRITABLE, B])
+        );
+
+        let mut crawler = Crawler::new(MemorySource::new(Vec::new()), Pubkey::new_unique());
+        crawler
+            .add_ix_filter(IxProgramIdFilter::new(B))
+            .add_account_index(IxAccount::unparsed("loaded", 0))
+            .add_account_index(IxAccount::unparsed("payer", 1));
+        let records = crawler
+            .process_transaction(&Signature::default(), &tx)
+            .unwrap();
+
+        let accounts = records
+            .iter()
+            .map(|record| {
+                (
+                    record.label.as_str(),
+                    record.value.clone(),
+                    record.program_id.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            accounts,
+            vec![
+                ("loaded", json!(WRITABLE), Some(B)),
+                ("payer", json!(PAYER), Some(B)),
+            ]
+        );
+    }
+
+    /// A crawler over one transaction per slot from 1 to `slots`, listed newest first, with block times equal to
+    /// their slots. Returns the signatures by slot too.
+    fn slot_crawler(slots: u64) -> (Crawler, Vec<String>) {
+        let address = Pubkey::new_unique();
+        let signatures = (0..=slots)
+            .map(|_| Signature::new_unique().to_string())
+            .collect::<Vec<_>>();
+        let transactions = (1..=slots)
+            .map(|slot| {
+                let mut tx = transaction(
+                    &signatures[slot as usize],
+                    slot,
+                    &[&address.to_string()],
+                    &[],
+                    &[],
+                    &[],
+                );
+                tx.block_time = Some(slot as UnixTimestamp);
+                tx
+            })
+            .collect();
+
+        (
+            Crawler::new(MemorySource::new(transactions), address),
+            signatures,
+        )
+    }
+
+    #[tokio::test]
+    async fn signature_pages_stop_at_the_lower_bound() {
+        let (mut crawler, signatures) = slot_crawler(1500);
+        let newest_first = |from: usize, to: usize| {
+            (to..=from)
+                .rev()
+                .map(|slot| signatures[slot].clone())
+                .collect::<Vec<_>>()
+        };
+
+        // Without bounds a full page points at the next one.
+        let page = crawler.get_signature_page(None, None).await.unwrap();
+        assert_eq!(page.signatures, newest_first(1500, 501));
+        assert_eq!(
+            page.next.map(|sig| sig.to_string()),
+            Some(signatures[501].clone())
+        );
+
+        // Crossing the lower slot bound ends the listing, and signatures above the upper one are skipped.
+        crawler.set_min_slot(600).set_max_slot(1400);
+        let page = crawler.get_signature_page(None, None).await.unwrap();
+        assert_eq!(page.signatures, newest_first(1400, 600));
+        assert!(page.next.is_none());
+
+        let mut checkpoint = Checkpoint::new(&crawler.address.to_string());
+        crawler
+            .get_all_signatures_for_id(&mut checkpoint, None, &mut CrawlReport::default())
+            .await
+            .unwrap();
+        assert_eq!(checkpoint.signatures, newest_first(1400, 600));
+        assert!(checkpoint.listing_complete);
+
+        // The time window bounds the listing the same way.
+        crawler.min_slot = None;
+        crawler.max_slot = None;
+        crawler.set_time_window(Some(700), Some(1200));
+        let page = crawler.get_signature_page(None, None).await.unwrap();
+        assert_eq!(page.signatures, newest_first(1200, 700));
+        assert!(page.next.is_none());
+    }
+
+    /// Fails to fetch the transactions of the given signatures.
+    struct FailingSource {
+        inner: MemorySource,
+        failing: Vec<String>,
+    }
+
+    #[async_trait::async_trait]
+    impl TransactionSource for FailingSource {
+        async fn get_signatures_for_address(
+            &self,
+            address: &Pubkey,
+            config: GetConfirmedSignaturesForAddress2Config,
+        ) -> Result<
+            Vec<solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature>,
+            CrawlError,
+        > {
+            self.inner.get_signatures_for_address(address, config).await
+        }
+
+        async fn get_transaction(
+            &self,
+            signature: &Signature,
+        ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+            match self.failing.contains(&signature.to_string()) {
+                true => Err(CrawlError::TransactionNotFound(signature.to_string())),
+                false => self.inner.get_transaction(signature).await,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn incremental_runs_hand_off_and_retry_failed_signatures() {
+        let address = Pubkey::new_unique().to_string();
+        let signatures = (0..=5)
+            .map(|_| Signature::new_unique().to_string())
+            .collect::<Vec<_>>();
+        let accounts = (0..=5)
+            .map(|_| Pubkey::new_unique().to_string())
+            .collect::<Vec<_>>();
+        // One transaction per slot, each invoking A with an account of its own.
+        let transactions = |slots: u64| {
+            (1..=slots)
+                .map(|slot| {
+                    let slot_account: &[&str] = &[&accounts[slot as usize]];
+                    let ix: TestIx = (A, slot_account);
+                    transaction(
+                        &signatures[slot as usize],
+                        slot,
+                        &[&address, A],
+                        &[ix],
+                        &[],
+                        &[],
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let crawler = |slots: u64, failing: &[usize]| {
+            let source = FailingSource {
+                inner: MemorySource::new(transactions(slots)),
+                failing: failing
+                    .iter()
+                    .map(|slot| signatures[*slot].clone())
+                    .collect(),
+            };
+            let mut crawler = Crawler::new(source, Pubkey::from_str(&address).unwrap());
+            crawler.add_account_index(IxAccount::unparsed("account", 0));
+            crawler
+        };
+        let crawled = |slots: &[usize]| {
+            HashMap::from([(
+                "account".to_string(),
+                slots
+                    .iter()
+                    .map(|slot| accounts[*slot].clone())
+                    .collect::<HashSet<_>>(),
+            )])
+        };
+
+        // The first run lists slots 1 to 3, but slot 2 fails to fetch.
+        let (new_accounts, state, report) = crawler(3, &[2])
+            .run_incremental(CrawlState::default())
+            .await
+            .unwrap();
+        assert_eq!(new_accounts, crawled(&[1, 3]));
+        assert_eq!(state.newest_signature.as_ref(), Some(&signatures[3]));
+        assert_eq!(state.failed, vec![signatures[2].clone()]);
+        assert_eq!(report.failed.len(), 1);
+
+        // The second run only lists the slots after the first run's newest signature, and retries slot 2.
+        let (new_accounts, state, report) = crawler(5, &[]).run_incremental(state).await.unwrap();
+        assert_eq!(new_accounts, crawled(&[2, 4, 5]));
+        assert_eq!(state.accounts, crawled(&[1, 2, 3, 4, 5]));
+        assert_eq!(state.newest_signature.as_ref(), Some(&signatures[5]));
+        assert!(state.failed.is_empty());
+        assert!(report.is_complete());
+
+        // With nothing new to list, the state is carried over as is.
+        let (new_accounts, state, _) = crawler(5, &[]).run_incremental(state).await.unwrap();
+        assert!(new_accounts.is_empty());
+        assert_eq!(state.newest_signature.as_ref(), Some(&signatures[5]));
+        assert_eq!(state.accounts, crawled(&[1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn named_accounts_of_idl_decoded_instructions_are_extracted() {
+        const MINT: &str = "Mint111111111111111111111111111111111111111";
+        let idl = Idl::from_json(
+            r#"{
+                "name": "test",
+                "instructions": [{
+                    "name": "mintNft",
+                    "accounts": [{ "name": "payer" }, { "name": "mint" }],
+                    "args": []
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let mut tx = serde_json::to_value(transaction(
+            "sig",
+            1,
+            &[A, B, MINT],
+            &[(A, &[B, MINT])],
+            &[],
+            &[],
+        ))
+        .unwrap();
+        let data = bs58::encode(crate::anchor::instruction_discriminator("mintNft")).into_string();
+        *tx.pointer_mut("/transaction/message/instructions/0/data")
+            .unwrap() = json!(data);
+        let tx = serde_json::from_value(tx).unwrap();
+
+        let mut crawler = Crawler::new(MemorySource::new(Vec::new()), Pubkey::new_unique());
+        crawler
+            .add_idl(A, idl)
+            .add_account_index(IxAccount::parsed("mint"))
+            .add_account_index(IxAccount::unparsed("first", 0));
+        let records = crawler
+            .process_transaction(&Signature::default(), &tx)
+            .unwrap();
+
+        let accounts = records
+            .iter()
+            .map(|record| (record.label.as_str(), record.value.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(accounts, vec![("mint", json!(MINT)), ("first", json!(B))]);
+    }
 }
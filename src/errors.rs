@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, Serialize, Deserialize)]
 pub enum CrawlError {
     #[error("no account data found")]
     MissingAccount(String),
@@ -13,4 +14,61 @@ pub enum CrawlError {
 
     #[error("Failed to parse signature: {0}")]
     SignatureParseFailed(String),
+
+    #[error("checkpoint failed with error: {0} for file: {1}")]
+    CheckpointFailed(String, String),
+
+    #[error("failed to fetch {0} transactions")]
+    IncompleteCrawl(usize),
+
+    #[error("unsupported transaction encoding: {0} for signature: {1}")]
+    UnsupportedEncoding(String, String),
+
+    #[error("unparsed instruction in transaction: {0}")]
+    UnparsedInstruction(String),
+
+    #[error("transaction not found: {0}")]
+    TransactionNotFound(String),
+
+    #[error("failed to parse IDL: {0}")]
+    IdlParseFailed(String),
+
+    #[error("invalid regex: {0}")]
+    InvalidRegex(String),
+
+    #[error("writing records failed with error: {0}")]
+    SinkFailed(String),
+
+    #[error("cache failed with error: {0} for file: {1}")]
+    CacheFailed(String, String),
+
+    #[error("store failed with error: {0}")]
+    StoreFailed(String),
+
+    #[error("transaction source failed with error: {0} for: {1}")]
+    SourceFailed(String, String),
+
+    #[error("batch request failed with error: {0}")]
+    BatchFailed(String),
+
+    #[error("batch request rejected with error: {0}")]
+    BatchRejected(String),
+
+    #[error("blocking task failed with error: {0}")]
+    BlockingTaskFailed(String),
+
+    #[error("request not found in cassette: {0}")]
+    NotInCassette(String),
+}
+
+impl CrawlError {
+    /// Returns false for errors that will be the same on every attempt, which the crawler doesn't retry.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(
+            self,
+            CrawlError::TransactionNotFound(_)
+                | CrawlError::NotInCassette(_)
+                | CrawlError::BatchRejected(_)
+        )
+    }
 }
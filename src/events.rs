@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+use crate::{
+    anchor::event_discriminator,
+    extractors::TxExtractor,
+    idl::{Idl, IdlField},
+    logs::{log_messages, program_data},
+};
+
+/// An event decoded from a transaction's logs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedEvent {
+    pub name: String,
+    /// The program that emitted the event.
+    pub program_id: Option<String>,
+    pub data: Value,
+}
+
+/// Decodes Anchor events from the "Program data:" lines of a transaction's logs, either with the events of an
+/// IDL or with a single event layout. Used as a transaction extractor it emits each event's fields as a JSON
+/// object under the event name.
+pub struct EventDecoder {
+    idl: Idl,
+    program_id: Option<String>,
+}
+
+impl EventDecoder {
+    /// Decode the events declared in an IDL.
+    pub fn from_idl(idl: Idl) -> Self {
+        Self {
+            idl,
+            program_id: None,
+        }
+    }
+
+    /// Decode a single event with the given field layout. The discriminator is computed from the event name.
+    pub fn new(name: &str, fields: Vec<IdlField>) -> Self {
+        Self::with_discriminator(name, event_discriminator(name), fields)
+    }
+
+    /// Decode a single event with the given discriminator and field layout.
+    pub fn with_discriminator(name: &str, discriminator: [u8; 8], fields: Vec<IdlField>) -> Self {
+        Self::from_idl(Idl::from_event(name, discriminator, fields))
+    }
+
+    /// Only decode events emitted by the given program.
+    pub fn in_program(mut self, program_id: &str) -> Self {
+        self.program_id = Some(program_id.to_string());
+        self
+    }
+
+    /// Decode every event in the transaction's logs that matches one of the decoder's events.
+    pub fn decode(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<DecodedEvent> {
+        program_data(log_messages(tx))
+            .into_iter()
+            .filter(|(program_id, _)| {
+                self.program_id
+                    .iter()
+                    .all(|expected| *program_id == Some(expected.as_str()))
+            })
+            .filter_map(|(program_id, data)| {
+                let (name, fields) = self.idl.decode_event(&data)?;
+                Some(DecodedEvent {
+                    name: name.to_string(),
+                    program_id: program_id.map(|id| id.to_string()),
+                    data: fields,
+                })
+            })
+            .collect()
+    }
+}
+
+impl TxExtractor for EventDecoder {
+    fn extract(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<(String, Value)> {
+        self.decode(tx)
+            .into_iter()
+            .map(|event| (event.name, event.data))
+            .collect()
+    }
+}
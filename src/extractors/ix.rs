@@ -0,0 +1,142 @@
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::parse_instruction::ParsedInstruction;
+
+use super::*;
+use crate::filters::instruction_data;
+
+/// The layout of a fixed-size field in raw instruction data. Integers are little endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I64,
+    /// Decoded as a string, since JSON numbers can't hold 128-bit integers.
+    U128,
+    Pubkey,
+}
+
+impl DataType {
+    fn size(&self) -> usize {
+        match self {
+            DataType::Bool | DataType::U8 => 1,
+            DataType::U16 => 2,
+            DataType::U32 => 4,
+            DataType::U64 | DataType::I64 => 8,
+            DataType::U128 => 16,
+            DataType::Pubkey => 32,
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Value> {
+        let value = match self {
+            DataType::Bool => json!(bytes[0] != 0),
+            DataType::U8 => json!(bytes[0]),
+            DataType::U16 => json!(u16::from_le_bytes(bytes.try_into().ok()?)),
+            DataType::U32 => json!(u32::from_le_bytes(bytes.try_into().ok()?)),
+            DataType::U64 => json!(u64::from_le_bytes(bytes.try_into().ok()?)),
+            DataType::I64 => json!(i64::from_le_bytes(bytes.try_into().ok()?)),
+            DataType::U128 => json!(u128::from_le_bytes(bytes.try_into().ok()?).to_string()),
+            DataType::Pubkey => json!(Pubkey::new_from_array(bytes.try_into().ok()?).to_string()),
+        };
+        Some(value)
+    }
+}
+
+enum FieldSource {
+    Pointer(String),
+    Data { offset: usize, data_type: DataType },
+}
+
+/// Instruction Fields are values other than accounts users wish to retrieve from an instruction, kept with
+/// their JSON type. For parsed instructions, including ones decoded with an IDL, they are looked up by JSON
+/// pointer, e.g. "/info/amount" or "/type". For unparsed instructions they are decoded from the raw data.
+pub struct IxField {
+    label: String,
+    source: FieldSource,
+}
+
+impl IxField {
+    /// A value looked up in a parsed instruction by JSON pointer.
+    pub fn pointer(label: &str, pointer: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            source: FieldSource::Pointer(pointer.to_string()),
+        }
+    }
+
+    /// A fixed-size value decoded from the Base58 instruction data of an unparsed instruction at `offset`.
+    pub fn data(label: &str, offset: usize, data_type: DataType) -> Self {
+        Self {
+            label: label.to_string(),
+            source: FieldSource::Data { offset, data_type },
+        }
+    }
+
+    /// A little endian u64 at `offset` in the instruction data, e.g. an amount after an 8-byte discriminator.
+    pub fn u64_le(label: &str, offset: usize) -> Self {
+        Self::data(label, offset, DataType::U64)
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Extract the field from an instruction. `decoded` is the instruction as decoded by an IDL, if there is one.
+    pub fn extract(
+        &self,
+        ix: &UiParsedInstruction,
+        decoded: Option<&ParsedInstruction>,
+    ) -> Option<Value> {
+        match &self.source {
+            FieldSource::Pointer(pointer) => {
+                let parsed = match ix {
+                    UiParsedInstruction::Parsed(ix) => Some(ix),
+                    UiParsedInstruction::PartiallyDecoded(_) => decoded,
+                };
+                parsed.and_then(|ix| ix.parsed.pointer(pointer)).cloned()
+            }
+            FieldSource::Data { offset, data_type } => {
+                let data = instruction_data(ix)?;
+                let end = offset.checked_add(data_type.size())?;
+                let bytes = data.get(*offset..end)?;
+                data_type.decode(bytes)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_transaction_status::UiPartiallyDecodedInstruction;
+
+    fn instruction(data: &[u8]) -> UiParsedInstruction {
+        UiParsedInstruction::PartiallyDecoded(UiPartiallyDecodedInstruction {
+            program_id: "Program111111111111111111111111111111111111".to_string(),
+            accounts: vec![],
+            data: bs58::encode(data).into_string(),
+        })
+    }
+
+    #[test]
+    fn data_fields_outside_the_data_are_skipped() {
+        let ix = instruction(&[0, 7, 0]);
+
+        assert_eq!(
+            IxField::data("amount", 1, DataType::U16).extract(&ix, None),
+            Some(json!(7))
+        );
+        assert_eq!(
+            IxField::data("amount", 2, DataType::U16).extract(&ix, None),
+            None
+        );
+        assert_eq!(
+            IxField::u64_le("amount", usize::MAX).extract(&ix, None),
+            None
+        );
+    }
+}
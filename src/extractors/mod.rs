@@ -0,0 +1,58 @@
+use serde_json::Value;
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiParsedInstruction};
+
+pub mod ix;
+pub mod tx;
+
+pub use ix::*;
+pub use tx::*;
+
+/// This trait defines the interface for custom extraction logic that `IxAccount` can't express. It is called for
+/// every instruction that passes the instruction filters and returns the `(label, value)` pairs found in it.
+pub trait IxExtractor {
+    fn extract(
+        &self,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+        ix: &UiParsedInstruction,
+    ) -> Vec<(String, Value)>;
+}
+
+/// Any closure taking the transaction and instruction and returning labeled values can be used as an extractor.
+/// Values can be anything that converts into JSON, such as strings, numbers or bools.
+impl<F, V> IxExtractor for F
+where
+    F: Fn(&EncodedConfirmedTransactionWithStatusMeta, &UiParsedInstruction) -> Vec<(String, V)>,
+    V: Into<Value>,
+{
+    fn extract(
+        &self,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+        ix: &UiParsedInstruction,
+    ) -> Vec<(String, Value)> {
+        self(tx, ix)
+            .into_iter()
+            .map(|(label, value)| (label, value.into()))
+            .collect()
+    }
+}
+
+/// This trait defines the interface for extraction logic that applies to a whole transaction rather than to its
+/// instructions, such as balance changes. It is called once for every transaction that passes the transaction
+/// filters, whether or not any of its instructions pass the instruction filters.
+pub trait TxExtractor {
+    fn extract(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<(String, Value)>;
+}
+
+/// Any closure taking the transaction and returning labeled values can be used as a transaction extractor.
+impl<F, V> TxExtractor for F
+where
+    F: Fn(&EncodedConfirmedTransactionWithStatusMeta) -> Vec<(String, V)>,
+    V: Into<Value>,
+{
+    fn extract(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<(String, Value)> {
+        self(tx)
+            .into_iter()
+            .map(|(label, value)| (label, value.into()))
+            .collect()
+    }
+}
@@ -0,0 +1,156 @@
+use regex::Regex;
+use serde_json::json;
+
+use super::*;
+use crate::{
+    balances::{sol_balance_changes, token_balance_changes, transaction_fee},
+    errors::CrawlError,
+    logs::{log_messages, program_logs},
+};
+
+/// Extracts the token transfers of a transaction from its token balance changes, without parsing instructions.
+/// For every mint whose balances changed it emits the mint under "mint" and a "token_transfer" object with the
+/// owners whose balance went down in "from" and the owners whose balance went up in "to". The token account
+/// address stands in for the owner when the transaction doesn't record owners.
+pub struct TokenTransfers;
+
+impl TxExtractor for TokenTransfers {
+    fn extract(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<(String, Value)> {
+        let mut mints: Vec<(String, Vec<String>, Vec<String>)> = Vec::new();
+
+        for change in token_balance_changes(tx) {
+            let delta = change.delta();
+            if delta == 0 {
+                continue;
+            }
+
+            let position = match mints.iter().position(|(mint, ..)| mint == &change.mint) {
+                Some(position) => position,
+                None => {
+                    mints.push((change.mint.clone(), Vec::new(), Vec::new()));
+                    mints.len() - 1
+                }
+            };
+
+            let (_, from, to) = &mut mints[position];
+            let owner = change.owner.unwrap_or(change.account);
+            if delta < 0 {
+                from.push(owner);
+            } else {
+                to.push(owner);
+            }
+        }
+
+        mints
+            .into_iter()
+            .flat_map(|(mint, from, to)| {
+                let transfer = json!({ "mint": mint, "from": from, "to": to });
+                [
+                    ("mint".to_string(), json!(mint)),
+                    ("token_transfer".to_string(), transfer),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Extracts the SOL balance changes of a transaction. Each account whose balance changed is emitted under
+/// "sol_balance_change" as `{"account": <address>, "delta": <lamports>}`, with negative deltas for accounts that
+/// paid. Deltas are JSON numbers; lamport balances always fit in an i64.
+pub struct SolBalanceChanges {
+    accounts: Option<Vec<String>>,
+}
+
+impl SolBalanceChanges {
+    /// Extract changes for every account in the transaction.
+    pub fn all() -> Self {
+        Self { accounts: None }
+    }
+
+    /// Extract changes only for the given accounts, e.g. a candy machine's treasury wallet.
+    pub fn only(accounts: &[&str]) -> Self {
+        Self {
+            accounts: Some(accounts.iter().map(|a| a.to_string()).collect()),
+        }
+    }
+}
+
+impl TxExtractor for SolBalanceChanges {
+    fn extract(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<(String, Value)> {
+        sol_balance_changes(tx)
+            .into_iter()
+            .filter(|change| change.delta() != 0)
+            .filter(|change| {
+                self.accounts
+                    .iter()
+                    .all(|accounts| accounts.contains(&change.account))
+            })
+            .map(|change| {
+                let value = json!({ "account": change.account, "delta": change.delta() as i64 });
+                ("sol_balance_change".to_string(), value)
+            })
+            .collect()
+    }
+}
+
+/// Extracts the fee paid by a transaction in lamports under "fee".
+pub struct TransactionFee;
+
+impl TxExtractor for TransactionFee {
+    fn extract(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<(String, Value)> {
+        transaction_fee(tx)
+            .map(|fee| vec![("fee".to_string(), json!(fee))])
+            .unwrap_or_default()
+    }
+}
+
+/// Captures values from log messages with a regular expression. Every match emits each named capture group
+/// under the group's name, e.g. `r"sale price: (?P<price>\d+)"` emits the price under "price". Programs often
+/// only reveal values such as sale prices or error codes through their logs.
+pub struct LogCapture {
+    regex: Regex,
+    program_id: Option<String>,
+}
+
+impl LogCapture {
+    pub fn new(pattern: &str) -> Result<Self, CrawlError> {
+        let regex = Regex::new(pattern).map_err(|err| CrawlError::InvalidRegex(err.to_string()))?;
+
+        Ok(Self {
+            regex,
+            program_id: None,
+        })
+    }
+
+    /// Only capture from messages logged while the given program was executing.
+    pub fn in_program(mut self, program_id: &str) -> Self {
+        self.program_id = Some(program_id.to_string());
+        self
+    }
+}
+
+impl TxExtractor for LogCapture {
+    fn extract(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<(String, Value)> {
+        let mut values = Vec::new();
+
+        for log in program_logs(log_messages(tx)) {
+            if !self
+                .program_id
+                .iter()
+                .all(|program_id| log.program_id == Some(program_id.as_str()))
+            {
+                continue;
+            }
+
+            for captures in self.regex.captures_iter(log.message) {
+                for name in self.regex.capture_names().flatten() {
+                    if let Some(capture) = captures.name(name) {
+                        values.push((name.to_string(), json!(capture.as_str())));
+                    }
+                }
+            }
+        }
+
+        values
+    }
+}
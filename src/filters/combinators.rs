@@ -0,0 +1,178 @@
+use super::*;
+
+/// Passes when every one of its filters passes. An empty group always passes.
+/// Works for both transaction and instruction filters; to mix filter types box them, e.g.
+/// `AllOf::new(vec![Box::new(a) as Box<dyn IxFilter + Send + Sync>, Box::new(b)])`.
+pub struct AllOf<F> {
+    filters: Vec<F>,
+}
+
+impl<F> AllOf<F> {
+    pub fn new(filters: Vec<F>) -> Self {
+        Self { filters }
+    }
+}
+
+impl<F: TxFilter> TxFilter for AllOf<F> {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        self.filters.iter().all(|filter| filter.filter(tx))
+    }
+}
+
+impl<F: IxFilter> IxFilter for AllOf<F> {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        self.filters.iter().all(|filter| filter.filter(ix))
+    }
+
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        self.filters
+            .iter()
+            .all(|filter| filter.filter_with_context(ctx))
+    }
+}
+
+/// Passes when at least one of its filters passes. An empty group never passes.
+pub struct AnyOf<F> {
+    filters: Vec<F>,
+}
+
+impl<F> AnyOf<F> {
+    pub fn new(filters: Vec<F>) -> Self {
+        Self { filters }
+    }
+}
+
+impl<F: TxFilter> TxFilter for AnyOf<F> {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        self.filters.iter().any(|filter| filter.filter(tx))
+    }
+}
+
+impl<F: IxFilter> IxFilter for AnyOf<F> {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        self.filters.iter().any(|filter| filter.filter(ix))
+    }
+
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        self.filters
+            .iter()
+            .any(|filter| filter.filter_with_context(ctx))
+    }
+}
+
+/// Passes when the wrapped filter does not.
+pub struct Not<F> {
+    filter: F,
+}
+
+impl<F> Not<F> {
+    pub fn new(filter: F) -> Self {
+        Self { filter }
+    }
+}
+
+impl<F: TxFilter> TxFilter for Not<F> {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        !self.filter.filter(tx)
+    }
+}
+
+impl<F: IxFilter> IxFilter for Not<F> {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        !self.filter.filter(ix)
+    }
+
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        !self.filter.filter_with_context(ctx)
+    }
+}
+
+/// Passes when both filters pass. Built with `.and()`.
+pub struct And<A, B> {
+    left: A,
+    right: B,
+}
+
+impl<A: TxFilter, B: TxFilter> TxFilter for And<A, B> {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        self.left.filter(tx) && self.right.filter(tx)
+    }
+}
+
+impl<A: IxFilter, B: IxFilter> IxFilter for And<A, B> {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        self.left.filter(ix) && self.right.filter(ix)
+    }
+
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        self.left.filter_with_context(ctx) && self.right.filter_with_context(ctx)
+    }
+}
+
+/// Passes when either filter passes. Built with `.or()`.
+pub struct Or<A, B> {
+    left: A,
+    right: B,
+}
+
+impl<A: TxFilter, B: TxFilter> TxFilter for Or<A, B> {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        self.left.filter(tx) || self.right.filter(tx)
+    }
+}
+
+impl<A: IxFilter, B: IxFilter> IxFilter for Or<A, B> {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        self.left.filter(ix) || self.right.filter(ix)
+    }
+
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        self.left.filter_with_context(ctx) || self.right.filter_with_context(ctx)
+    }
+}
+
+/// Combinator methods for transaction filters, e.g. `SuccessfulTxFilter.and(has_program_id.not())`.
+pub trait TxFilterExt: TxFilter + Sized {
+    fn and<F: TxFilter>(self, other: F) -> And<Self, F> {
+        And {
+            left: self,
+            right: other,
+        }
+    }
+
+    fn or<F: TxFilter>(self, other: F) -> Or<Self, F> {
+        Or {
+            left: self,
+            right: other,
+        }
+    }
+
+    fn not(self) -> Not<Self> {
+        Not::new(self)
+    }
+}
+
+impl<T: TxFilter> TxFilterExt for T {}
+
+/// Combinator methods for instruction filters, e.g. `program_a.and(data_x).or(program_b.and(IxMintToFilter.not()))`.
+pub trait IxFilterExt: IxFilter + Sized {
+    fn and<F: IxFilter>(self, other: F) -> And<Self, F> {
+        And {
+            left: self,
+            right: other,
+        }
+    }
+
+    fn or<F: IxFilter>(self, other: F) -> Or<Self, F> {
+        Or {
+            left: self,
+            right: other,
+        }
+    }
+
+    fn not(self) -> Not<Self> {
+        Not::new(self)
+    }
+}
+
+impl<T: IxFilter> IxFilterExt for T {}
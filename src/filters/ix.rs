@@ -1,4 +1,5 @@
 use super::*;
+use crate::anchor::instruction_discriminator;
 
 /// This filter passes through instructions that match the equality specified by the variant and only
 /// applies to PartiallyDecoded instructions. Fully parsed instructions are automatically passed through.
@@ -71,6 +72,74 @@ impl IxFilter for IxDataFilter {
     }
 }
 
+/// This filter decodes the Base58 instruction data and passes through instructions whose data contains `bytes`
+/// starting at `offset`. Only applies to PartiallyDecoded instructions.
+pub struct IxDataAtOffsetFilter {
+    offset: usize,
+    bytes: Vec<u8>,
+}
+
+impl IxDataAtOffsetFilter {
+    pub fn new(offset: usize, bytes: &[u8]) -> Self {
+        Self {
+            offset,
+            bytes: bytes.to_vec(),
+        }
+    }
+}
+
+impl IxFilter for IxDataAtOffsetFilter {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        instruction_data(ix)
+            .and_then(|data| {
+                let end = self.offset.checked_add(self.bytes.len())?;
+                data.get(self.offset..end)
+                    .map(|range| range == self.bytes.as_slice())
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// This filter passes through instructions whose decoded data starts with the given bytes,
+/// e.g. an instruction tag without the arguments that follow it.
+pub struct IxDataPrefixFilter {
+    filter: IxDataAtOffsetFilter,
+}
+
+impl IxDataPrefixFilter {
+    pub fn new(prefix: &[u8]) -> Self {
+        Self {
+            filter: IxDataAtOffsetFilter::new(0, prefix),
+        }
+    }
+}
+
+impl IxFilter for IxDataPrefixFilter {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        self.filter.filter(ix)
+    }
+}
+
+/// This filter passes through Anchor instructions by name, matching the 8-byte discriminator Anchor puts at the
+/// start of the instruction data. Both "mint_nft" and the IDL form "mintNft" are accepted.
+pub struct IxAnchorDiscriminatorFilter {
+    filter: IxDataPrefixFilter,
+}
+
+impl IxAnchorDiscriminatorFilter {
+    pub fn new(instruction_name: &str) -> Self {
+        Self {
+            filter: IxDataPrefixFilter::new(&instruction_discriminator(instruction_name)),
+        }
+    }
+}
+
+impl IxFilter for IxAnchorDiscriminatorFilter {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        self.filter.filter(ix)
+    }
+}
+
 /// This filter only applies to fully parsed instructions, and passes through any instruction with the type "mintTo".
 /// This filter is useful for getting the mintTo instruction from SPL token calls.
 pub struct IxMintToFilter;
@@ -138,3 +207,88 @@ impl IxFilter for IxHasAccountAtIndexFilter {
         }
     }
 }
+
+/// This filter passes through top-level instructions and drops inner instructions invoked through CPI.
+/// Without a context every instruction is treated as top-level.
+pub struct IxIsTopLevel;
+
+impl IxFilter for IxIsTopLevel {
+    fn filter(&self, _ix: &UiParsedInstruction) -> bool {
+        true
+    }
+
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        !ctx.is_inner()
+    }
+}
+
+/// This filter passes through inner instructions that were invoked through CPI by the specified program.
+pub struct IxInvokedBy {
+    program_id: String,
+}
+
+impl IxInvokedBy {
+    pub fn new(program_id: &str) -> Self {
+        Self {
+            program_id: program_id.to_string(),
+        }
+    }
+}
+
+impl IxFilter for IxInvokedBy {
+    fn filter(&self, _ix: &UiParsedInstruction) -> bool {
+        false
+    }
+
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        ctx.parent_program_id == Some(self.program_id.as_str())
+    }
+}
+
+/// This filter passes through inner instructions whose top-level instruction passes the wrapped filter.
+/// For example, `IxIsInnerOf::new(IxProgramIdFilter::new(CMV2_PROGRAM_ID))` combined with `IxMintToFilter`
+/// keeps only the mintTo instructions made while minting from Candy Machine.
+pub struct IxIsInnerOf<F> {
+    filter: F,
+}
+
+impl<F: IxFilter> IxIsInnerOf<F> {
+    pub fn new(filter: F) -> Self {
+        Self { filter }
+    }
+}
+
+impl<F: IxFilter> IxFilter for IxIsInnerOf<F> {
+    fn filter(&self, _ix: &UiParsedInstruction) -> bool {
+        false
+    }
+
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        ctx.outer()
+            .map(|outer| self.filter.filter_with_context(&outer))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_transaction_status::UiPartiallyDecodedInstruction;
+
+    fn instruction(data: &[u8]) -> UiParsedInstruction {
+        UiParsedInstruction::PartiallyDecoded(UiPartiallyDecodedInstruction {
+            program_id: "Program111111111111111111111111111111111111".to_string(),
+            accounts: vec![],
+            data: bs58::encode(data).into_string(),
+        })
+    }
+
+    #[test]
+    fn data_at_offset_outside_the_data_is_filtered_out() {
+        let ix = instruction(&[1, 2, 3, 4]);
+
+        assert!(IxDataAtOffsetFilter::new(1, &[2, 3]).filter(&ix));
+        assert!(!IxDataAtOffsetFilter::new(3, &[4, 5]).filter(&ix));
+        assert!(!IxDataAtOffsetFilter::new(usize::MAX, &[4]).filter(&ix));
+    }
+}
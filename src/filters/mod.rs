@@ -1,11 +1,14 @@
 use crate::constants::*;
 use solana_transaction_status::{
-    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiMessage, UiParsedInstruction,
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction, UiMessage, UiParsedInstruction,
 };
 
+pub mod combinators;
 pub mod ix;
 pub mod tx;
 
+pub use combinators::*;
 pub use ix::*;
 pub use tx::*;
 
@@ -17,4 +20,138 @@ pub trait TxFilter {
 /// This trait defines the interface for creating a filter that is applied to all instructions.
 pub trait IxFilter {
     fn filter(&self, ix: &UiParsedInstruction) -> bool;
+
+    /// Filter an instruction knowing where it sits in the transaction. The crawler always calls this method;
+    /// only filters that care about an instruction's position need to implement it.
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        self.filter(ctx.instruction)
+    }
+}
+
+/// An instruction along with its position in the transaction.
+#[derive(Debug, Clone, Copy)]
+pub struct IxContext<'a> {
+    pub instruction: &'a UiParsedInstruction,
+    /// The index of the top-level instruction, or of the one that invoked it for inner instructions.
+    pub index: usize,
+    /// The position among the top-level instruction's inner instructions, if this is an inner instruction.
+    pub inner_index: Option<usize>,
+    /// The invocation depth: 1 for top-level instructions, 2 for instructions they invoke and so on.
+    /// None when it can't be recovered from the transaction logs.
+    pub stack_height: Option<u32>,
+    /// The program that invoked this instruction through CPI. When the stack height is unknown this falls back
+    /// to the top-level instruction's program.
+    pub parent_program_id: Option<&'a str>,
+    /// The top-level instruction this inner instruction belongs to.
+    pub outer_instruction: Option<&'a UiParsedInstruction>,
+}
+
+impl<'a> IxContext<'a> {
+    /// The context of a top-level instruction.
+    pub fn top_level(instruction: &'a UiParsedInstruction, index: usize) -> Self {
+        Self {
+            instruction,
+            index,
+            inner_index: None,
+            stack_height: Some(1),
+            parent_program_id: None,
+            outer_instruction: None,
+        }
+    }
+
+    pub fn is_inner(&self) -> bool {
+        self.inner_index.is_some()
+    }
+
+    /// The context of the top-level instruction this inner instruction belongs to.
+    pub fn outer(&self) -> Option<IxContext<'a>> {
+        self.outer_instruction
+            .map(|outer| IxContext::top_level(outer, self.index))
+    }
+}
+
+/// Any closure taking a transaction can be used as a transaction filter.
+impl<F: Fn(&EncodedConfirmedTransactionWithStatusMeta) -> bool> TxFilter for F {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        self(tx)
+    }
+}
+
+/// Any closure taking an instruction can be used as an instruction filter.
+impl<F: Fn(&UiParsedInstruction) -> bool> IxFilter for F {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        self(ix)
+    }
+}
+
+impl TxFilter for Box<dyn TxFilter + Send + Sync> {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        self.as_ref().filter(tx)
+    }
+}
+
+impl IxFilter for Box<dyn IxFilter + Send + Sync> {
+    fn filter(&self, ix: &UiParsedInstruction) -> bool {
+        self.as_ref().filter(ix)
+    }
+
+    fn filter_with_context(&self, ctx: &IxContext) -> bool {
+        self.as_ref().filter_with_context(ctx)
+    }
+}
+
+/// Returns the Base58 decoded data of a PartiallyDecoded instruction. Parsed instructions and data that isn't valid
+/// Base58 return `None`.
+pub fn instruction_data(ix: &UiParsedInstruction) -> Option<Vec<u8>> {
+    match ix {
+        UiParsedInstruction::PartiallyDecoded(ix) => bs58::decode(&ix.data).into_vec().ok(),
+        UiParsedInstruction::Parsed(_) => None,
+    }
+}
+
+/// Returns the program id of an instruction.
+pub fn instruction_program_id(ix: &UiParsedInstruction) -> &str {
+    match ix {
+        UiParsedInstruction::Parsed(ix) => &ix.program_id,
+        UiParsedInstruction::PartiallyDecoded(ix) => &ix.program_id,
+    }
+}
+
+/// Returns every account key used by a transaction, including the accounts loaded from address lookup tables
+/// by versioned transactions. For raw messages the keys are in the order instruction account indices refer to:
+/// static keys, then writable loaded addresses, then readonly loaded addresses.
+/// Transactions that aren't JSON encoded have no readable account keys and return an empty list.
+pub fn transaction_account_keys(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<String> {
+    let mut keys = match &tx.transaction.transaction {
+        EncodedTransaction::Json(ui_tx) => match &ui_tx.message {
+            UiMessage::Raw(msg) => msg.account_keys.clone(),
+            UiMessage::Parsed(msg) => msg.account_keys.iter().map(|a| a.pubkey.clone()).collect(),
+        },
+        _ => return Vec::new(),
+    };
+
+    let loaded_addresses = match tx.transaction.meta.as_ref().map(|m| &m.loaded_addresses) {
+        Some(OptionSerializer::Some(loaded)) => Some(loaded),
+        _ => None,
+    };
+
+    if let Some(loaded) = loaded_addresses {
+        let loaded = loaded.writable.iter().chain(loaded.readonly.iter());
+        let is_parsed = matches!(
+            &tx.transaction.transaction,
+            EncodedTransaction::Json(ui_tx) if matches!(ui_tx.message, UiMessage::Parsed(_))
+        );
+        if is_parsed {
+            // Parsed messages already list loaded addresses in their account keys, so only add missing ones.
+            for address in loaded {
+                if !keys.contains(address) {
+                    keys.push(address.clone());
+                }
+            }
+        } else {
+            keys.extend(loaded.cloned());
+        }
+    }
+
+    keys
 }
@@ -1,6 +1,12 @@
 use solana_transaction_status::option_serializer::OptionSerializer;
 
 use super::*;
+use crate::{
+    anchor::event_discriminator,
+    balances::{sol_balance_changes, token_balance_changes, transaction_fee},
+    errors::CrawlError,
+    logs::{log_messages, program_data, program_logs, LogPattern},
+};
 
 /// This filter passes through all successful transactions, rejecting any with errors.
 pub struct SuccessfulTxFilter;
@@ -45,17 +51,9 @@ pub struct Cmv2TxFilter;
 
 impl TxFilter for Cmv2TxFilter {
     fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
-        match &tx.transaction.transaction {
-            EncodedTransaction::Json(ui_tx) => match &ui_tx.message {
-                UiMessage::Raw(msg) => msg.account_keys.contains(&CMV2_PROGRAM_ID.to_string()),
-                UiMessage::Parsed(msg) => msg
-                    .account_keys
-                    .iter()
-                    .map(|a| a.pubkey.clone())
-                    .any(|a| a == *CMV2_PROGRAM_ID),
-            },
-            _ => panic!("not Json encoded"),
-        }
+        transaction_account_keys(tx)
+            .iter()
+            .any(|a| a == CMV2_PROGRAM_ID)
     }
 }
 
@@ -74,21 +72,14 @@ impl TxHasProgramId {
 
 impl TxFilter for TxHasProgramId {
     fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
-        match &tx.transaction.transaction {
-            EncodedTransaction::Json(ui_tx) => match &ui_tx.message {
-                UiMessage::Raw(msg) => msg.account_keys.contains(&self.program_id),
-                UiMessage::Parsed(msg) => msg
-                    .account_keys
-                    .iter()
-                    .map(|a| a.pubkey.clone())
-                    .any(|a| a == self.program_id),
-            },
-            _ => panic!("not Json encoded"),
-        }
+        transaction_account_keys(tx)
+            .iter()
+            .any(|a| a == &self.program_id)
     }
 }
 
 /// This filter passes through all transactions where the provided address is a signer.
+/// Accounts loaded from address lookup tables can never sign, so only the static account keys are checked.
 pub struct TxHasSigner {
     address: String,
 }
@@ -105,13 +96,237 @@ impl TxFilter for TxHasSigner {
     fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
         match &tx.transaction.transaction {
             EncodedTransaction::Json(ui_tx) => match &ui_tx.message {
-                UiMessage::Raw(_) => panic!("Not a parsed tx message"),
+                // The first `num_required_signatures` account keys are the signers.
+                UiMessage::Raw(msg) => msg
+                    .account_keys
+                    .iter()
+                    .take(msg.header.num_required_signatures as usize)
+                    .any(|a| a == &self.address),
                 UiMessage::Parsed(msg) => msg
                     .account_keys
                     .iter()
                     .any(|a| a.pubkey == self.address && a.signer),
             },
-            _ => panic!("not Json encoded"),
+            // Only JSON encoded transactions have readable account keys.
+            _ => false,
+        }
+    }
+}
+
+/// This filter passes through transactions where a token account's balance changed by at least `min_delta`
+/// base units in either direction, optionally only for the given mint and owner.
+pub struct TxTokenBalanceChanged {
+    mint: Option<String>,
+    owner: Option<String>,
+    min_delta: u64,
+}
+
+impl TxTokenBalanceChanged {
+    pub fn new(mint: Option<&str>, owner: Option<&str>, min_delta: u64) -> Self {
+        Self {
+            mint: mint.map(|mint| mint.to_string()),
+            owner: owner.map(|owner| owner.to_string()),
+            min_delta,
+        }
+    }
+}
+
+impl TxFilter for TxTokenBalanceChanged {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        token_balance_changes(tx).iter().any(|change| {
+            let delta = change.delta().unsigned_abs();
+            delta > 0
+                && delta >= self.min_delta as u128
+                && self.mint.iter().all(|mint| &change.mint == mint)
+                && self
+                    .owner
+                    .iter()
+                    .all(|owner| change.owner.as_ref() == Some(owner))
+        })
+    }
+}
+
+/// This filter passes through transactions where the account's SOL balance went down or up by at least
+/// the given number of lamports. With 0 lamports any decrease or increase passes, but an unchanged balance doesn't.
+pub struct TxSolBalanceChanged {
+    account: String,
+    direction: BalanceDirection,
+    lamports: u64,
+}
+
+enum BalanceDirection {
+    Lost,
+    Gained,
+}
+
+impl TxSolBalanceChanged {
+    /// The account lost at least `lamports`.
+    pub fn lost(account: &str, lamports: u64) -> Self {
+        Self {
+            account: account.to_string(),
+            direction: BalanceDirection::Lost,
+            lamports,
+        }
+    }
+
+    /// The account gained at least `lamports`.
+    pub fn gained(account: &str, lamports: u64) -> Self {
+        Self {
+            account: account.to_string(),
+            direction: BalanceDirection::Gained,
+            lamports,
+        }
+    }
+}
+
+impl TxFilter for TxSolBalanceChanged {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        sol_balance_changes(tx)
+            .iter()
+            .filter(|change| change.account == self.account)
+            .any(|change| {
+                let delta = change.delta();
+                let moved = match self.direction {
+                    BalanceDirection::Lost if delta < 0 => delta.unsigned_abs(),
+                    BalanceDirection::Gained if delta > 0 => delta.unsigned_abs(),
+                    _ => return false,
+                };
+                moved >= self.lamports as u128
+            })
+    }
+}
+
+/// This filter passes through transactions whose fee in lamports matches the equality specified by the variant.
+pub enum TxFeeFilter {
+    LessThan(u64),
+    LessThanOrEqual(u64),
+    EqualTo(u64),
+    GreaterThan(u64),
+    GreaterThanOrEqual(u64),
+}
+
+impl TxFilter for TxFeeFilter {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        match transaction_fee(tx) {
+            Some(fee) => match self {
+                TxFeeFilter::LessThan(n) => fee < *n,
+                TxFeeFilter::LessThanOrEqual(n) => fee <= *n,
+                TxFeeFilter::EqualTo(n) => fee == *n,
+                TxFeeFilter::GreaterThan(n) => fee > *n,
+                TxFeeFilter::GreaterThanOrEqual(n) => fee >= *n,
+            },
+            None => false,
+        }
+    }
+}
+
+/// This filter passes through transactions with a log message matching a substring or regular expression,
+/// optionally only counting messages logged inside the given program's invocation frames.
+pub struct TxLogFilter {
+    pattern: LogPattern,
+    program_id: Option<String>,
+}
+
+impl TxLogFilter {
+    pub fn contains(substring: &str) -> Self {
+        Self {
+            pattern: LogPattern::contains(substring),
+            program_id: None,
+        }
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self, CrawlError> {
+        Ok(Self {
+            pattern: LogPattern::regex(pattern)?,
+            program_id: None,
+        })
+    }
+
+    /// Only match messages logged while the given program was executing.
+    pub fn in_program(mut self, program_id: &str) -> Self {
+        self.program_id = Some(program_id.to_string());
+        self
+    }
+}
+
+impl TxFilter for TxLogFilter {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        program_logs(log_messages(tx)).iter().any(|log| {
+            self.program_id
+                .iter()
+                .all(|program_id| log.program_id == Some(program_id.as_str()))
+                && self.pattern.is_match(log.message)
+        })
+    }
+}
+
+/// This filter passes through transactions that emitted the named Anchor event, e.g. `TxEmitsEvent::new("SaleEvent")`,
+/// by matching the event discriminator at the start of "Program data:" log lines.
+pub struct TxEmitsEvent {
+    discriminator: [u8; 8],
+    program_id: Option<String>,
+}
+
+impl TxEmitsEvent {
+    pub fn new(event_name: &str) -> Self {
+        Self {
+            discriminator: event_discriminator(event_name),
+            program_id: None,
         }
     }
+
+    /// Only match events emitted by the given program.
+    pub fn in_program(mut self, program_id: &str) -> Self {
+        self.program_id = Some(program_id.to_string());
+        self
+    }
+}
+
+impl TxFilter for TxEmitsEvent {
+    fn filter(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> bool {
+        program_data(log_messages(tx))
+            .iter()
+            .any(|(program_id, data)| {
+                data.starts_with(&self.discriminator)
+                    && self
+                        .program_id
+                        .iter()
+                        .all(|expected| *program_id == Some(expected.as_str()))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::transaction;
+
+    const ACCOUNT: &str = "Account111111111111111111111111111111111111";
+
+    fn balance_change(pre: u64, post: u64) -> EncodedConfirmedTransactionWithStatusMeta {
+        let mut tx = transaction("sig", 1, &[ACCOUNT], &[], &[], &[]);
+        let meta = tx.transaction.meta.as_mut().unwrap();
+        meta.pre_balances = vec![pre];
+        meta.post_balances = vec![post];
+        tx
+    }
+
+    #[test]
+    fn sol_balance_direction_is_respected() {
+        let lost = balance_change(100, 40);
+        let gained = balance_change(40, 100);
+        let unchanged = balance_change(100, 100);
+
+        assert!(TxSolBalanceChanged::lost(ACCOUNT, 60).filter(&lost));
+        assert!(!TxSolBalanceChanged::lost(ACCOUNT, 61).filter(&lost));
+        assert!(TxSolBalanceChanged::gained(ACCOUNT, 60).filter(&gained));
+        assert!(!TxSolBalanceChanged::gained(ACCOUNT, 60).filter(&lost));
+
+        // Zero lamports still requires a change in the given direction.
+        assert!(TxSolBalanceChanged::lost(ACCOUNT, 0).filter(&lost));
+        assert!(!TxSolBalanceChanged::lost(ACCOUNT, 0).filter(&gained));
+        assert!(!TxSolBalanceChanged::lost(ACCOUNT, 0).filter(&unchanged));
+        assert!(!TxSolBalanceChanged::gained(ACCOUNT, 0).filter(&lost));
+        assert!(!TxSolBalanceChanged::gained(ACCOUNT, 0).filter(&unchanged));
+    }
 }
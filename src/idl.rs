@@ -0,0 +1,541 @@
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{
+    parse_instruction::ParsedInstruction, UiPartiallyDecodedInstruction,
+};
+use std::{fs, path::Path};
+
+use crate::{
+    anchor::{event_discriminator, instruction_discriminator},
+    errors::CrawlError,
+};
+
+/// An Anchor IDL, used to decode the instructions of programs the RPC node can't parse.
+/// Decoded instructions have the same shape as the node's own parsed instructions:
+/// `{"type": <instruction name>, "info": {<account name>: <address>, <arg name>: <value>, ...}}`.
+/// An argument with the same name as one of the instruction's accounts is put under `info.args` instead,
+/// so the account isn't overwritten.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "IdlDefinition")]
+pub struct Idl {
+    pub name: String,
+    pub instructions: Vec<IdlInstruction>,
+    pub types: Vec<IdlTypeDefinition>,
+    pub events: Vec<IdlEvent>,
+    discriminators: Vec<[u8; 8]>,
+    event_discriminators: Vec<[u8; 8]>,
+}
+
+/// An IDL as it is written in JSON. Every `Idl` is deserialized through this so its discriminators are always
+/// computed, however it is loaded.
+#[derive(Deserialize)]
+struct IdlDefinition {
+    name: String,
+    instructions: Vec<IdlInstruction>,
+    #[serde(default)]
+    types: Vec<IdlTypeDefinition>,
+    #[serde(default)]
+    events: Vec<IdlEvent>,
+}
+
+impl From<IdlDefinition> for Idl {
+    fn from(idl: IdlDefinition) -> Self {
+        Self {
+            discriminators: idl
+                .instructions
+                .iter()
+                .map(|ix| instruction_discriminator(&ix.name))
+                .collect(),
+            event_discriminators: idl
+                .events
+                .iter()
+                .map(|event| event_discriminator(&event.name))
+                .collect(),
+            name: idl.name,
+            instructions: idl.instructions,
+            types: idl.types,
+            events: idl.events,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub accounts: Vec<IdlAccountItem>,
+    pub args: Vec<IdlField>,
+}
+
+/// An instruction account, or a group of accounts declared by a composite `Accounts` struct.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum IdlAccountItem {
+    Accounts {
+        name: String,
+        accounts: Vec<IdlAccountItem>,
+    },
+    Account {
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+}
+
+impl IdlField {
+    pub fn new(name: &str, ty: IdlType) -> Self {
+        Self {
+            name: name.to_string(),
+            ty,
+        }
+    }
+}
+
+/// An event emitted with Anchor's `emit!`, logged as a base64 "Program data:" line.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlEvent {
+    pub name: String,
+    pub fields: Vec<IdlField>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlTypeDefinition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefinitionTy,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum IdlTypeDefinitionTy {
+    Struct { fields: Vec<IdlField> },
+    Enum { variants: Vec<IdlEnumVariant> },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlEnumVariant {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Option<IdlEnumFields>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum IdlEnumFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    F32,
+    U64,
+    I64,
+    F64,
+    U128,
+    I128,
+    Bytes,
+    String,
+    #[serde(alias = "pubkey")]
+    PublicKey,
+    Defined(String),
+    Option(Box<IdlType>),
+    #[serde(rename = "coption")]
+    COption(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+}
+
+impl Idl {
+    /// Load an IDL from a JSON file such as the ones `anchor build` writes to `target/idl`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CrawlError> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path).map_err(|err| {
+            CrawlError::IdlParseFailed(format!("{} for file: {}", err, path.display()))
+        })?;
+
+        Self::from_json(&json)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, CrawlError> {
+        serde_json::from_str(json).map_err(|err| CrawlError::IdlParseFailed(err.to_string()))
+    }
+
+    /// An IDL describing a single event, for programs that don't publish an IDL.
+    pub fn from_event(name: &str, discriminator: [u8; 8], fields: Vec<IdlField>) -> Self {
+        Self {
+            name: String::new(),
+            instructions: Vec::new(),
+            types: Vec::new(),
+            events: vec![IdlEvent {
+                name: name.to_string(),
+                fields,
+            }],
+            discriminators: Vec::new(),
+            event_discriminators: vec![discriminator],
+        }
+    }
+
+    /// Decode an event from the data of a "Program data:" log line. Returns the event name and its fields, or
+    /// `None` if the data doesn't start with the discriminator of one of the IDL's events.
+    pub fn decode_event(&self, data: &[u8]) -> Option<(&str, Value)> {
+        let (discriminator, mut event_data) = (data.get(..8)?, data.get(8..)?);
+
+        let position = self
+            .event_discriminators
+            .iter()
+            .position(|d| d.as_slice() == discriminator)?;
+        let event = &self.events[position];
+
+        let fields = self.decode_fields(&event.fields, &mut event_data)?;
+        Some((&event.name, fields))
+    }
+
+    /// Decode an instruction sent to the program this IDL describes. Returns `None` if the data doesn't start
+    /// with the discriminator of one of its instructions or doesn't match the instruction's arguments.
+    pub fn decode_instruction(
+        &self,
+        ix: &UiPartiallyDecodedInstruction,
+    ) -> Option<ParsedInstruction> {
+        let data = bs58::decode(&ix.data).into_vec().ok()?;
+        let (discriminator, mut args_data) = (data.get(..8)?, data.get(8..)?);
+
+        let position = self
+            .discriminators
+            .iter()
+            .position(|d| d.as_slice() == discriminator)?;
+        let instruction = &self.instructions[position];
+
+        let mut account_names = Vec::new();
+        flatten_accounts(&instruction.accounts, &mut account_names);
+
+        let mut info = Map::new();
+        let mut shadowed_args = Map::new();
+        for arg in instruction.args.iter() {
+            let value = self.decode_type(&arg.ty, &mut args_data)?;
+            if account_names.contains(&arg.name.as_str()) {
+                shadowed_args.insert(arg.name.clone(), value);
+            } else {
+                info.insert(arg.name.clone(), value);
+            }
+        }
+
+        for (name, address) in account_names.into_iter().zip(ix.accounts.iter()) {
+            info.insert(name.to_string(), Value::String(address.clone()));
+        }
+        if !shadowed_args.is_empty() {
+            info.insert("args".to_string(), Value::Object(shadowed_args));
+        }
+
+        Some(ParsedInstruction {
+            program: self.name.clone(),
+            program_id: ix.program_id.clone(),
+            parsed: json!({ "type": instruction.name, "info": info }),
+        })
+    }
+
+    fn decode_type(&self, ty: &IdlType, data: &mut &[u8]) -> Option<Value> {
+        let value = match ty {
+            IdlType::Bool => Value::Bool(take::<1>(data)?[0] != 0),
+            IdlType::U8 => json!(take::<1>(data)?[0]),
+            IdlType::I8 => json!(take::<1>(data)?[0] as i8),
+            IdlType::U16 => json!(u16::from_le_bytes(take(data)?)),
+            IdlType::I16 => json!(i16::from_le_bytes(take(data)?)),
+            IdlType::U32 => json!(u32::from_le_bytes(take(data)?)),
+            IdlType::I32 => json!(i32::from_le_bytes(take(data)?)),
+            IdlType::F32 => json!(f32::from_le_bytes(take(data)?)),
+            IdlType::U64 => json!(u64::from_le_bytes(take(data)?)),
+            IdlType::I64 => json!(i64::from_le_bytes(take(data)?)),
+            IdlType::F64 => json!(f64::from_le_bytes(take(data)?)),
+            // JSON numbers can't hold 128-bit integers, so these are decoded as strings.
+            IdlType::U128 => json!(u128::from_le_bytes(take(data)?).to_string()),
+            IdlType::I128 => json!(i128::from_le_bytes(take(data)?).to_string()),
+            IdlType::Bytes => json!(take_slice(data)?),
+            IdlType::String => json!(String::from_utf8(take_slice(data)?.to_vec()).ok()?),
+            IdlType::PublicKey => json!(Pubkey::new_from_array(take(data)?).to_string()),
+            IdlType::Option(inner) => match take::<1>(data)?[0] {
+                0 => Value::Null,
+                _ => self.decode_type(inner, data)?,
+            },
+            IdlType::COption(inner) => match u32::from_le_bytes(take(data)?) {
+                0 => Value::Null,
+                _ => self.decode_type(inner, data)?,
+            },
+            IdlType::Vec(inner) => {
+                let len = u32::from_le_bytes(take(data)?) as usize;
+                // Guard against lengths that can't possibly fit in the remaining data.
+                if len > data.len() {
+                    return None;
+                }
+                let items = (0..len)
+                    .map(|_| self.decode_type(inner, data))
+                    .collect::<Option<Vec<Value>>>()?;
+                Value::Array(items)
+            }
+            IdlType::Array(inner, len) => {
+                let items = (0..*len)
+                    .map(|_| self.decode_type(inner, data))
+                    .collect::<Option<Vec<Value>>>()?;
+                Value::Array(items)
+            }
+            IdlType::Defined(name) => {
+                let definition = self.types.iter().find(|def| &def.name == name)?;
+                match &definition.ty {
+                    IdlTypeDefinitionTy::Struct { fields } => self.decode_fields(fields, data)?,
+                    IdlTypeDefinitionTy::Enum { variants } => {
+                        let variant = variants.get(take::<1>(data)?[0] as usize)?;
+                        let fields = match &variant.fields {
+                            None => return Some(json!(variant.name)),
+                            Some(IdlEnumFields::Named(fields)) => {
+                                self.decode_fields(fields, data)?
+                            }
+                            Some(IdlEnumFields::Tuple(types)) => Value::Array(
+                                types
+                                    .iter()
+                                    .map(|ty| self.decode_type(ty, data))
+                                    .collect::<Option<Vec<Value>>>()?,
+                            ),
+                        };
+                        let mut object = Map::new();
+                        object.insert(variant.name.clone(), fields);
+                        Value::Object(object)
+                    }
+                }
+            }
+        };
+
+        Some(value)
+    }
+
+    fn decode_fields(&self, fields: &[IdlField], data: &mut &[u8]) -> Option<Value> {
+        let mut object = Map::new();
+        for field in fields {
+            object.insert(field.name.clone(), self.decode_type(&field.ty, data)?);
+        }
+        Some(Value::Object(object))
+    }
+}
+
+fn flatten_accounts<'a>(items: &'a [IdlAccountItem], names: &mut Vec<&'a str>) {
+    for item in items {
+        match item {
+            IdlAccountItem::Account { name } => names.push(name),
+            IdlAccountItem::Accounts { accounts, .. } => flatten_accounts(accounts, names),
+        }
+    }
+}
+
+/// Read a fixed number of bytes from the front of the data.
+fn take<const N: usize>(data: &mut &[u8]) -> Option<[u8; N]> {
+    let bytes = data.get(..N)?.try_into().ok()?;
+    *data = &data[N..];
+    Some(bytes)
+}
+
+/// Read a u32 length-prefixed byte slice from the front of the data.
+fn take_slice<'a>(data: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = u32::from_le_bytes(take(data)?) as usize;
+    let bytes = data.get(..len)?;
+    *data = &data[len..];
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idl() -> Idl {
+        Idl::from_json(
+            r#"{
+                "name": "test",
+                "instructions": [],
+                "types": [
+                    {
+                        "name": "Point",
+                        "type": { "kind": "struct", "fields": [
+                            { "name": "x", "type": "i32" },
+                            { "name": "y", "type": "i32" }
+                        ] }
+                    },
+                    {
+                        "name": "Shape",
+                        "type": { "kind": "enum", "variants": [
+                            { "name": "Empty" },
+                            { "name": "Circle", "fields": [{ "name": "radius", "type": "u16" }] },
+                            { "name": "Line", "fields": [{ "defined": "Point" }, { "defined": "Point" }] }
+                        ] }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn instruction(name: &str, args: &[u8], accounts: &[&str]) -> UiPartiallyDecodedInstruction {
+        let mut data = instruction_discriminator(name).to_vec();
+        data.extend_from_slice(args);
+        UiPartiallyDecodedInstruction {
+            program_id: "program".to_string(),
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+            data: bs58::encode(data).into_string(),
+        }
+    }
+
+    #[test]
+    fn deserialized_idls_decode_instructions() {
+        let idl: Idl = serde_json::from_value(json!({
+            "name": "test",
+            "instructions": [{
+                "name": "mintNft",
+                "accounts": [{ "name": "payer" }, { "name": "mint" }],
+                "args": [{ "name": "amount", "type": "u8" }]
+            }]
+        }))
+        .unwrap();
+
+        let decoded = idl
+            .decode_instruction(&instruction("mintNft", &[5], &["P", "M"]))
+            .unwrap();
+        assert_eq!(
+            decoded.parsed,
+            json!({ "type": "mintNft", "info": { "amount": 5, "payer": "P", "mint": "M" } })
+        );
+    }
+
+    #[test]
+    fn args_named_like_accounts_are_kept_apart() {
+        let idl = Idl::from_json(
+            r#"{
+                "name": "test",
+                "instructions": [{
+                    "name": "transfer",
+                    "accounts": [{ "name": "authority" }],
+                    "args": [
+                        { "name": "authority", "type": "publicKey" },
+                        { "name": "amount", "type": "u8" }
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let mut args = vec![1; 32];
+        args.push(9);
+        let decoded = idl
+            .decode_instruction(&instruction("transfer", &args, &["A"]))
+            .unwrap();
+        assert_eq!(
+            decoded.parsed,
+            json!({
+                "type": "transfer",
+                "info": {
+                    "authority": "A",
+                    "amount": 9,
+                    "args": { "authority": Pubkey::new_from_array([1; 32]).to_string() }
+                }
+            })
+        );
+    }
+
+    fn decode(ty: IdlType, data: &[u8]) -> Option<Value> {
+        let mut data = data;
+        let value = idl().decode_type(&ty, &mut data)?;
+        assert!(data.is_empty(), "{} bytes left over", data.len());
+        Some(value)
+    }
+
+    #[test]
+    fn decode_primitives() {
+        assert_eq!(decode(IdlType::Bool, &[1]), Some(json!(true)));
+        assert_eq!(decode(IdlType::I8, &[0xff]), Some(json!(-1)));
+        assert_eq!(decode(IdlType::U16, &[1, 2]), Some(json!(0x0201)));
+        assert_eq!(
+            decode(IdlType::I64, &(-5i64).to_le_bytes()),
+            Some(json!(-5))
+        );
+        assert_eq!(
+            decode(IdlType::U128, &u128::MAX.to_le_bytes()),
+            Some(json!(u128::MAX.to_string()))
+        );
+        assert_eq!(
+            decode(IdlType::String, &[3, 0, 0, 0, b'a', b'b', b'c']),
+            Some(json!("abc"))
+        );
+        assert_eq!(
+            decode(IdlType::PublicKey, &[0; 32]),
+            Some(json!(Pubkey::default().to_string()))
+        );
+    }
+
+    #[test]
+    fn decode_containers() {
+        let option = || IdlType::Option(Box::new(IdlType::U8));
+        assert_eq!(decode(option(), &[0]), Some(Value::Null));
+        assert_eq!(decode(option(), &[1, 7]), Some(json!(7)));
+
+        assert_eq!(
+            decode(IdlType::Vec(Box::new(IdlType::U8)), &[2, 0, 0, 0, 4, 5]),
+            Some(json!([4, 5]))
+        );
+        assert_eq!(
+            decode(IdlType::Array(Box::new(IdlType::U8), 3), &[1, 2, 3]),
+            Some(json!([1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn decode_defined_types() {
+        assert_eq!(
+            decode(
+                IdlType::Defined("Point".to_string()),
+                &[1, 0, 0, 0, 2, 0, 0, 0]
+            ),
+            Some(json!({ "x": 1, "y": 2 }))
+        );
+
+        let shape = || IdlType::Defined("Shape".to_string());
+        assert_eq!(decode(shape(), &[0]), Some(json!("Empty")));
+        assert_eq!(
+            decode(shape(), &[1, 10, 0]),
+            Some(json!({ "Circle": { "radius": 10 } }))
+        );
+        assert_eq!(
+            decode(
+                shape(),
+                &[2, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0]
+            ),
+            Some(json!({ "Line": [{ "x": 1, "y": 2 }, { "x": 3, "y": 4 }] }))
+        );
+    }
+
+    #[test]
+    fn decode_rejects_invalid_data() {
+        // Too short for the type.
+        assert_eq!(decode(IdlType::U64, &[1, 2, 3]), None);
+        // A vector length that can't fit in the remaining data.
+        assert_eq!(
+            decode(
+                IdlType::Vec(Box::new(IdlType::U8)),
+                &[0xff, 0xff, 0xff, 0xff, 1]
+            ),
+            None
+        );
+        // An enum variant that doesn't exist.
+        assert_eq!(decode(IdlType::Defined("Shape".to_string()), &[3]), None);
+        // An undefined type.
+        assert_eq!(decode(IdlType::Defined("Missing".to_string()), &[0]), None);
+    }
+}
@@ -21,7 +21,7 @@
 //!
 //! ```rust,ignore
 //! use anyhow::Result;
-//! use solana_client::rpc_client::RpcClient;
+//! use solana_client::nonblocking::rpc_client::RpcClient;
 //! use solana_transaction_crawler::{
 //!     crawler::{Crawler, IxAccount},
 //!     filters::{IxNumberAccounts, SuccessfulTxFilter, TxHasProgramId},
@@ -31,7 +31,7 @@
 //! #[tokio::main]
 //! async fn main() -> Result<()> {
 //!     // Set up Solana RPC client.
-//!     let client = RpcClient::new("https://rpc.ankr.com/solana");
+//!     let client = RpcClient::new("https://rpc.ankr.com/solana".to_string());
 //!
 //!     // The pubkey of the account you want to crawl for transactions. In this example we use the DeGods CMV1 pubkey.
 //!     let candy_machine_id = pubkey!("9MynErYQ5Qi6obp4YwwdoDmXkZ1hYVtPUqYmJJ3rZ9Kn");
@@ -62,8 +62,9 @@
 //!         .add_ix_filter(ix_num_accounts)
 //!         .add_account_index(mint_account);
 //!
-//!     // Run the Crawler.
-//!     let crawled_accounts = crawler.run().await?;
+//!     // Run the Crawler. The report lists any transactions that could not be fetched.
+//!     let (crawled_accounts, report) = crawler.run().await?;
+//!     println!("Failed transactions: {:?}", report.failed.len());
 //!
 //!     // We labeled our account "mint" so we look it up by the label.
 //!     let mint_addresses = &crawled_accounts["mint"];
@@ -77,20 +78,20 @@
 //!
 //! ```rust,ignore
 //! use anyhow::Result;
-//! use solana_client::rpc_client::RpcClient;
+//! use solana_client::nonblocking::rpc_client::RpcClient;
 //! use solana_transaction_crawler::crawler::Crawler;
 //! use solana_program::pubkey;
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<()> {
 //!     // Set up Solana RPC client.
-//!     let client = RpcClient::new("https://rpc.ankr.com/solana");
+//!     let client = RpcClient::new("https://rpc.ankr.com/solana".to_string());
 //!
 //!     // The pubkey of the candy machine v2 account you want to crawl for transactions.
 //!     let candy_machine_id = pubkey!("CMaBroFssWYUXxxwmASEQXF9RtBiyq3XwA1Qi1ViTTV");
 //!
 //!     // Create the Crawler object.
-//!     let crawled_accounts = Crawler::get_cmv2_mints(client, candy_machine_id).await?;
+//!     let (crawled_accounts, _report) = Crawler::get_cmv2_mints(client, candy_machine_id).await?;
 //!
 //!     // This method returns both 'mint' and 'metadata' accounts under the respective labels.
 //!     let mint_addresses = &crawled_accounts["mint"];
@@ -102,8 +103,228 @@
 //! }
 //! ```
 //!
+//! Crawlers aren't tied to a live node: anything implementing `sources::TransactionSource` can be passed to
+//! `Crawler::new`. `FileSource` reads saved transactions from a directory or JSONL file and `MemorySource` serves
+//! them from a vector, which makes it possible to run crawls offline against fixtures.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::{crawler::Crawler, sources::FileSource};
+//!
+//! let source = FileSource::open("fixtures/candy_machine.jsonl")?;
+//! let (crawled_accounts, _report) = Crawler::get_cmv2_mints(source, candy_machine_id).await?;
+//! ```
+//!
+//! Large crawls can be streamed so accounts are available while signatures are still being listed and
+//! transactions fetched, rather than all at once at the end of `run`.
+//!
+//! ```rust,ignore
+//! use futures::StreamExt;
+//!
+//! let crawler = Crawler::create_cmv2_mints(client, candy_machine_id);
+//! let mut accounts = Box::pin(crawler.stream());
+//! while let Some(item) = accounts.next().await {
+//!     match item {
+//!         Ok(record) => println!("{}: {}", record.label, record.value_string()),
+//!         Err(err) => eprintln!("{err}"),
+//!     }
+//! }
+//! ```
+//!
+//! Filters can be combined into boolean trees with `AllOf`, `AnyOf` and `Not`, or with the `.and()`, `.or()` and
+//! `.not()` methods, and the result passed to a single `add_ix_filter` or `add_tx_filter` call.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::filters::{IxDataFilter, IxFilterExt, IxMintToFilter, IxProgramIdFilter};
+//!
+//! let filter = IxProgramIdFilter::new(PROGRAM_A)
+//!     .and(IxDataFilter::new(DATA_X))
+//!     .or(IxProgramIdFilter::new(PROGRAM_B).and(IxMintToFilter.not()));
+//! crawler.add_ix_filter(filter);
+//! ```
+//!
+//! Instruction data can be matched on decoded bytes rather than the exact Base58 payload: `IxDataPrefixFilter` and
+//! `IxDataAtOffsetFilter` compare a byte range, and `IxAnchorDiscriminatorFilter` matches Anchor instructions by name.
+//!
+//! ```rust,ignore
+//! crawler
+//!     .add_ix_filter(IxProgramIdFilter::new(CMV2_PROGRAM_ID))
+//!     .add_ix_filter(IxAnchorDiscriminatorFilter::new("mintNft"));
+//! ```
+//!
+//! Programs the RPC node can't parse can be decoded with their Anchor IDL. Accounts are then extracted by the
+//! names the IDL gives them instead of by index, and instruction arguments are decoded into the instruction's
+//! JSON "info" just like the node's own parsed instructions.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::idl::Idl;
+//!
+//! crawler
+//!     .add_idl(CMV2_PROGRAM_ID, Idl::load("idl/candy_machine.json")?)
+//!     .add_account_index(IxAccount::idl("mintNft", "mint"))
+//!     .add_account_index(IxAccount::idl("mintNft", "metadata"));
+//! ```
+//!
+//! Values other than accounts are extracted with `IxField`, either by JSON pointer from parsed instructions or
+//! as fixed-size fields of the raw instruction data. They keep their JSON type in the resulting `CrawlRecord`s.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::extractors::{DataType, IxField};
+//!
+//! crawler
+//!     .add_field(IxField::pointer("amount", "/info/amount"))
+//!     .add_field(IxField::pointer("type", "/type"))
+//!     .add_field(IxField::data("max_supply", 1, DataType::U64));
+//! ```
+//!
+//! Token movements can be followed through the transaction's token balances instead of each program's instruction
+//! layout. `TxTokenBalanceChanged` filters on balance changes and the `TokenTransfers` transaction extractor emits
+//! every mint that moved along with the owners it moved from and to.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::{extractors::TokenTransfers, filters::TxTokenBalanceChanged};
+//!
+//! crawler
+//!     .add_tx_filter(TxTokenBalanceChanged::new(Some(MINT), None, 1))
+//!     .add_tx_extractor(TokenTransfers);
+//! ```
+//!
+//! SOL movements and fees work the same way. Combined with program filters they can, for example, total the mint
+//! revenue a candy machine's treasury received.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::{extractors::{SolBalanceChanges, TransactionFee}, filters::TxSolBalanceChanged};
+//!
+//! crawler
+//!     .add_tx_filter(TxHasProgramId::new(CMV2_PROGRAM_ID))
+//!     .add_tx_filter(TxSolBalanceChanged::gained(TREASURY, 1))
+//!     .add_tx_extractor(SolBalanceChanges::only(&[TREASURY]))
+//!     .add_tx_extractor(TransactionFee);
+//! ```
+//!
+//! Log messages can be filtered by substring or regular expression, optionally only inside one program's
+//! invocation frames, and regex capture groups can be extracted from them.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::{extractors::LogCapture, filters::TxLogFilter};
+//!
+//! crawler
+//!     .add_tx_filter(TxLogFilter::regex(r"^Program log: Instruction: (Buy|ExecuteSale)")?.in_program(MARKETPLACE))
+//!     .add_tx_extractor(LogCapture::new(r"price: (?P<price>\d+)")?.in_program(MARKETPLACE));
+//! ```
+//!
+//! Anchor events are decoded from the base64 "Program data:" log lines with `EventDecoder`, using an IDL or a
+//! single event layout, and `TxEmitsEvent` filters on them by name.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::{events::EventDecoder, filters::TxEmitsEvent, idl::Idl};
+//!
+//! crawler
+//!     .add_tx_filter(TxEmitsEvent::new("SaleEvent").in_program(MARKETPLACE))
+//!     .add_tx_extractor(EventDecoder::from_idl(Idl::load("idl/marketplace.json")?).in_program(MARKETPLACE));
+//! ```
+//!
+//! Instruction filters also see where each instruction sits in its transaction through `IxContext`: whether it is
+//! top-level, which top-level instruction it belongs to and which program invoked it. `IxIsTopLevel`,
+//! `IxInvokedBy` and `IxIsInnerOf` filter on it, for example to keep only the `mintTo` instructions Candy Machine
+//! made through CPI.
+//!
+//! ```rust,ignore
+//! crawler
+//!     .add_ix_filter(IxMintToFilter)
+//!     .add_ix_filter(IxInvokedBy::new(CMV2_PROGRAM_ID));
+//! ```
+//!
+//! One-off filters and extraction logic can be written as closures. Annotate the argument types so they match
+//! the filter and extractor traits.
+//!
+//! ```rust,ignore
+//! use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiParsedInstruction};
+//!
+//! crawler
+//!     .add_ix_filter(|ix: &UiParsedInstruction| matches!(ix, UiParsedInstruction::PartiallyDecoded(_)))
+//!     .add_extractor(|_tx: &EncodedConfirmedTransactionWithStatusMeta, ix: &UiParsedInstruction| match ix {
+//!         UiParsedInstruction::PartiallyDecoded(ix) => vec![("data".to_string(), ix.data.clone())],
+//!         UiParsedInstruction::Parsed(_) => Vec::new(),
+//!     });
+//! ```
+//!
+//! `run_records` returns every extracted value as a `CrawlRecord` along with the signature, slot, block time and
+//! instruction it came from, including whether it was found in an inner instruction.
+//!
+//! ```rust,ignore
+//! let (records, _report) = crawler.run_records().await?;
+//! for record in records.iter().filter(|r| r.label == "mint") {
+//!     println!("{} created in {} at {:?}", record.value_string(), record.signature, record.block_time);
+//! }
+//! ```
+//!
+//! Records can be written out while the crawl runs with a `RecordSink`. JSON, JSON Lines and CSV sinks are built
+//! in, and every result type implements `Serialize` and `Deserialize`.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::sinks::CsvSink;
+//!
+//! let mut sink = CsvSink::create("mints.csv")?;
+//! let report = crawler.run_with_sink(&mut sink).await?;
+//! ```
+//!
+//! With the `sqlite` feature enabled, `SqliteStore` keeps fetched transactions, filter outcomes and extracted records
+//! in a local database. Repeated crawls only fetch transactions the store doesn't have, and the store can serve as
+//! a transaction source to re-run new filters offline.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::store::SqliteStore;
+//!
+//! let mut crawler = Crawler::create_cmv2_mints(client, candy_machine_id);
+//! crawler.set_store(SqliteStore::open("crawl.db")?);
+//! let (crawled_accounts, _report) = crawler.run().await?;
+//!
+//! // Later, without network access:
+//! let mut offline = Crawler::new(SqliteStore::open("crawl.db")?, candy_machine_id);
+//! ```
+//!
+//! Fetching and analysis can also be separated: `fetch_to_cache` saves the raw transactions once, and
+//! `analyze_cache` applies the current filters and extraction to them in seconds, which makes iterating on a new
+//! preset much faster.
+//!
+//! ```rust,ignore
+//! crawler.fetch_to_cache("cmv2.jsonl").await?;
+//!
+//! // Adjust filters and re-run as often as needed without touching the RPC node.
+//! let (crawled_accounts, _report) = crawler.analyze_cache("cmv2.jsonl")?;
+//! ```
+//!
+//! A crawl against a node can also be recorded to a cassette file with `CassetteRecorder` and replayed later
+//! with `CassetteSource`, which reproduces the run without network access.
+//!
+//! ```rust,ignore
+//! use solana_transaction_crawler::sources::{CassetteRecorder, CassetteSource};
+//!
+//! let recorder = CassetteRecorder::create(client, "cmv2.cassette.jsonl")?;
+//! let (recorded, _report) = Crawler::get_cmv2_mints(recorder, candy_machine_id).await?;
+//!
+//! let replay = CassetteSource::open("cmv2.cassette.jsonl")?;
+//! let (replayed, _report) = Crawler::get_cmv2_mints(replay, candy_machine_id).await?;
+//! assert_eq!(recorded, replayed);
+//! ```
+//!
 
+pub mod anchor;
+pub mod balances;
+mod blocking;
+pub mod checkpoint;
 pub mod constants;
 pub mod crawler;
 pub mod errors;
+pub mod events;
+pub mod extractors;
 pub mod filters;
+pub mod idl;
+pub mod logs;
+pub mod records;
+pub mod sinks;
+pub mod sources;
+pub mod store;
+
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,162 @@
+use regex::Regex;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+};
+
+use crate::errors::CrawlError;
+
+/// A log message along with the program whose invocation frame it was logged in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramLog<'a> {
+    /// The program executing when the message was logged, or None for messages outside any frame,
+    /// such as "Log truncated".
+    pub program_id: Option<&'a str>,
+    pub message: &'a str,
+}
+
+/// Returns the transaction's log messages, or an empty list if the node didn't return any.
+pub fn log_messages(tx: &EncodedConfirmedTransactionWithStatusMeta) -> &[String] {
+    match tx.transaction.meta.as_ref().map(|meta| &meta.log_messages) {
+        Some(OptionSerializer::Some(logs)) => logs,
+        _ => &[],
+    }
+}
+
+/// Attribute every log message to the program that logged it by tracking the "Program X invoke [n]" and
+/// "Program X success"/"Program X failed" lines that open and close each invocation frame. The invoke and
+/// success lines belong to the frame they open and close.
+pub fn program_logs(logs: &[String]) -> Vec<ProgramLog<'_>> {
+    let mut stack: Vec<&str> = Vec::new();
+
+    logs.iter()
+        .map(|message| {
+            let frame = message.strip_prefix("Program ").and_then(|rest| {
+                let (program_id, rest) = rest.split_once(' ')?;
+                if rest.starts_with("invoke [") {
+                    Some((program_id, true))
+                } else if rest == "success" || rest.starts_with("failed") {
+                    Some((program_id, false))
+                } else {
+                    None
+                }
+            });
+
+            match frame {
+                Some((program_id, true)) => {
+                    stack.push(program_id);
+                    ProgramLog {
+                        program_id: Some(program_id),
+                        message,
+                    }
+                }
+                Some((program_id, false)) => {
+                    if stack.last() == Some(&program_id) {
+                        stack.pop();
+                    }
+                    ProgramLog {
+                        program_id: Some(program_id),
+                        message,
+                    }
+                }
+                None => ProgramLog {
+                    program_id: stack.last().copied(),
+                    message,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Returns the base64 decoded data of every "Program data:" log line, as written by `sol_log_data` and Anchor's
+/// `emit!`, along with the program that logged it. Only the first data chunk of each line is returned.
+pub fn program_data(logs: &[String]) -> Vec<(Option<&str>, Vec<u8>)> {
+    program_logs(logs)
+        .into_iter()
+        .filter_map(|log| {
+            let encoded = log.message.strip_prefix("Program data: ")?;
+            let chunk = encoded.split_whitespace().next()?;
+            let data = base64::decode(chunk).ok()?;
+            Some((log.program_id, data))
+        })
+        .collect()
+}
+
+/// How a log message is matched: by substring or by regular expression.
+#[derive(Debug, Clone)]
+pub enum LogPattern {
+    Contains(String),
+    Regex(Regex),
+}
+
+impl LogPattern {
+    pub fn contains(substring: &str) -> Self {
+        LogPattern::Contains(substring.to_string())
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self, CrawlError> {
+        Regex::new(pattern)
+            .map(LogPattern::Regex)
+            .map_err(|err| CrawlError::InvalidRegex(err.to_string()))
+    }
+
+    pub fn is_match(&self, message: &str) -> bool {
+        match self {
+            LogPattern::Contains(substring) => message.contains(substring.as_str()),
+            LogPattern::Regex(regex) => regex.is_match(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn program_logs_follow_invocation_frames() {
+        let logs = logs(&[
+            "Program A invoke [1]",
+            "Program log: in A",
+            "Program B invoke [2]",
+            "Program log: in B",
+            "Program B failed: custom program error: 0x1",
+            "Program log: back in A",
+            "Program A success",
+            "Log truncated",
+        ]);
+
+        let programs = program_logs(&logs)
+            .iter()
+            .map(|log| log.program_id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            programs,
+            vec![
+                Some("A"),
+                Some("A"),
+                Some("B"),
+                Some("B"),
+                Some("B"),
+                Some("A"),
+                Some("A"),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn program_data_is_decoded_with_its_program() {
+        let logs = logs(&[
+            "Program A invoke [1]",
+            "Program data: AQID BAUG",
+            "Program data: not base64!",
+            "Program A success",
+        ]);
+
+        assert_eq!(program_data(&logs), vec![(Some("A"), vec![1, 2, 3])]);
+    }
+}
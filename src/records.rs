@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::clock::{Slot, UnixTimestamp};
+
+use crate::crawler::CrawledAccounts;
+
+/// A single value extracted by the crawler along with where it came from: the transaction, its slot and
+/// block time, and the instruction that produced it. Values extracted from the transaction as a whole,
+/// such as balance changes, have no instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrawlRecord {
+    /// The label the value was extracted under, e.g. "mint".
+    pub label: String,
+    /// The extracted value. Accounts are strings; fields keep the JSON type they were decoded as.
+    pub value: Value,
+    pub signature: String,
+    pub slot: Slot,
+    pub block_time: Option<UnixTimestamp>,
+    /// The index of the top-level instruction the value came from, or of the top-level instruction that
+    /// invoked it for inner instructions.
+    pub instruction_index: Option<usize>,
+    /// The position of the instruction among its parent's inner instructions, if it is an inner instruction.
+    pub inner_instruction_index: Option<usize>,
+    /// The program the instruction was sent to.
+    pub program_id: Option<String>,
+}
+
+impl CrawlRecord {
+    /// Returns true if the value came from an inner instruction invoked through CPI.
+    pub fn is_inner(&self) -> bool {
+        self.inner_instruction_index.is_some()
+    }
+
+    /// The value as a string: strings are returned as is and any other value as its JSON text.
+    pub fn value_string(&self) -> String {
+        match &self.value {
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        }
+    }
+}
+
+/// Reduce crawl records to the set of unique values found under each label, using `CrawlRecord::value_string`.
+pub fn crawled_accounts(records: &[CrawlRecord]) -> CrawledAccounts {
+    let mut accounts = CrawledAccounts::new();
+    for record in records {
+        accounts
+            .entry(record.label.clone())
+            .or_default()
+            .insert(record.value_string());
+    }
+    accounts
+}
@@ -0,0 +1,242 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{errors::CrawlError, records::CrawlRecord};
+
+/// This trait defines the interface for writing crawl records out as a crawl progresses. `Crawler::run_with_sink`
+/// calls `write` after every batch of transactions and `finish` once the crawl is done.
+pub trait RecordSink {
+    fn write(&mut self, records: &[CrawlRecord]) -> Result<(), CrawlError>;
+
+    /// Complete the output, e.g. by closing a JSON array, and flush it.
+    fn finish(&mut self) -> Result<(), CrawlError>;
+}
+
+/// Writes records as a single JSON array.
+pub struct JsonSink<W: Write> {
+    writer: W,
+    first: bool,
+    finished: bool,
+}
+
+impl<W: Write> JsonSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            first: true,
+            finished: false,
+        }
+    }
+}
+
+impl JsonSink<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, CrawlError> {
+        Ok(Self::new(create_file(path.as_ref())?))
+    }
+}
+
+impl<W: Write> RecordSink for JsonSink<W> {
+    fn write(&mut self, records: &[CrawlRecord]) -> Result<(), CrawlError> {
+        for record in records {
+            let separator = if self.first { "[\n" } else { ",\n" };
+            self.first = false;
+            self.writer
+                .write_all(separator.as_bytes())
+                .map_err(sink_error)?;
+            serde_json::to_writer(&mut self.writer, record).map_err(sink_error)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), CrawlError> {
+        // The array is only closed once, however often the sink is finished.
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+
+        let end = if self.first { "[]\n" } else { "\n]\n" };
+        self.writer.write_all(end.as_bytes()).map_err(sink_error)?;
+        self.writer.flush().map_err(sink_error)
+    }
+}
+
+/// Writes records as JSON Lines, one record per line.
+pub struct JsonlSink<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonlSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl JsonlSink<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, CrawlError> {
+        Ok(Self::new(create_file(path.as_ref())?))
+    }
+}
+
+impl<W: Write> RecordSink for JsonlSink<W> {
+    fn write(&mut self, records: &[CrawlRecord]) -> Result<(), CrawlError> {
+        for record in records {
+            serde_json::to_writer(&mut self.writer, record).map_err(sink_error)?;
+            self.writer.write_all(b"\n").map_err(sink_error)?;
+        }
+        self.writer.flush().map_err(sink_error)
+    }
+
+    fn finish(&mut self) -> Result<(), CrawlError> {
+        self.writer.flush().map_err(sink_error)
+    }
+}
+
+/// Writes records as CSV with a header row. Values that aren't strings are written as their JSON text.
+pub struct CsvSink<W: Write> {
+    writer: W,
+    header_written: bool,
+}
+
+const CSV_HEADER: &str =
+    "label,value,signature,slot,block_time,instruction_index,inner_instruction_index,program_id";
+
+impl<W: Write> CsvSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            header_written: false,
+        }
+    }
+
+    fn write_header(&mut self) -> Result<(), CrawlError> {
+        if !self.header_written {
+            self.header_written = true;
+            writeln!(self.writer, "{}", CSV_HEADER).map_err(sink_error)?;
+        }
+        Ok(())
+    }
+}
+
+impl CsvSink<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, CrawlError> {
+        Ok(Self::new(create_file(path.as_ref())?))
+    }
+}
+
+impl<W: Write> RecordSink for CsvSink<W> {
+    fn write(&mut self, records: &[CrawlRecord]) -> Result<(), CrawlError> {
+        self.write_header()?;
+
+        for record in records {
+            let fields = [
+                csv_field(&record.label),
+                csv_field(&record.value_string()),
+                csv_field(&record.signature),
+                record.slot.to_string(),
+                optional(record.block_time),
+                optional(record.instruction_index),
+                optional(record.inner_instruction_index),
+                csv_field(record.program_id.as_deref().unwrap_or_default()),
+            ];
+            writeln!(self.writer, "{}", fields.join(",")).map_err(sink_error)?;
+        }
+        self.writer.flush().map_err(sink_error)
+    }
+
+    fn finish(&mut self) -> Result<(), CrawlError> {
+        self.write_header()?;
+        self.writer.flush().map_err(sink_error)
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn create_file(path: &Path) -> Result<BufWriter<File>, CrawlError> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|err| CrawlError::SinkFailed(format!("{} for file: {}", err, path.display())))
+}
+
+fn sink_error<E: ToString>(err: E) -> CrawlError {
+    CrawlError::SinkFailed(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn record(label: &str, value: Value) -> CrawlRecord {
+        CrawlRecord {
+            label: label.to_string(),
+            value,
+            signature: "sig".to_string(),
+            slot: 7,
+            block_time: None,
+            instruction_index: Some(0),
+            inner_instruction_index: None,
+            program_id: Some("program".to_string()),
+        }
+    }
+
+    #[test]
+    fn json_sink_writes_one_array() {
+        let mut sink = JsonSink::new(Vec::new());
+        sink.finish().unwrap();
+        assert_eq!(String::from_utf8(sink.writer).unwrap(), "[]\n");
+
+        let mut sink = JsonSink::new(Vec::new());
+        sink.write(&[record("mint", json!("M1"))]).unwrap();
+        sink.write(&[]).unwrap();
+        sink.write(&[record("amount", json!(5))]).unwrap();
+        sink.finish().unwrap();
+        sink.finish().unwrap();
+
+        let written: Value = serde_json::from_slice(&sink.writer).unwrap();
+        assert_eq!(
+            written,
+            json!([
+                serde_json::to_value(record("mint", json!("M1"))).unwrap(),
+                serde_json::to_value(record("amount", json!(5))).unwrap(),
+            ])
+        );
+    }
+
+    #[test]
+    fn csv_sink_quotes_fields() {
+        let mut sink = CsvSink::new(Vec::new());
+        sink.write(&[
+            record("plain", json!("M1")),
+            record("with, comma", json!("say \"hi\"")),
+            record("line\nbreak", json!({ "a": 1, "b": 2 })),
+        ])
+        .unwrap();
+        sink.finish().unwrap();
+
+        assert_eq!(
+            String::from_utf8(sink.writer).unwrap(),
+            format!(
+                "{}\n{}\n{}\n{}\n",
+                CSV_HEADER,
+                "plain,M1,sig,7,,0,,program",
+                "\"with, comma\",\"say \"\"hi\"\"\",sig,7,,0,,program",
+                "\"line\nbreak\",\"{\"\"a\"\":1,\"\"b\"\":2}\",sig,7,,0,,program",
+            )
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use super::*;
+
+type SignaturesResponse = Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError>;
+type TransactionResponse = Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>;
+
+/// A single recorded response. Cassettes are JSONL files with one entry per line, in the order the
+/// requests were made.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum CassetteEntry<'a> {
+    Signatures {
+        request: SignaturesRequest,
+        response: &'a SignaturesResponse,
+    },
+    Transaction {
+        signature: String,
+        response: &'a TransactionResponse,
+    },
+}
+
+/// The owned form of `CassetteEntry`, read back when replaying.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ReplayEntry {
+    Signatures {
+        request: SignaturesRequest,
+        response: SignaturesResponse,
+    },
+    Transaction {
+        signature: String,
+        response: Box<TransactionResponse>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct SignaturesRequest {
+    address: String,
+    before: Option<String>,
+    until: Option<String>,
+    limit: Option<usize>,
+}
+
+impl SignaturesRequest {
+    fn new(address: &Pubkey, config: &GetConfirmedSignaturesForAddress2Config) -> Self {
+        Self {
+            address: address.to_string(),
+            before: config.before.map(|sig| sig.to_string()),
+            until: config.until.map(|sig| sig.to_string()),
+            limit: config.limit,
+        }
+    }
+}
+
+/// Wraps another transaction source and writes every `getSignaturesForAddress` page and every `getTransaction`
+/// response, including errors, to a cassette file. The cassette can be replayed later with `CassetteSource`.
+pub struct CassetteRecorder<S> {
+    inner: S,
+    path: PathBuf,
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl<S: TransactionSource> CassetteRecorder<S> {
+    /// Start recording to a new cassette file, replacing any existing file at `path`.
+    pub fn create<P: AsRef<Path>>(inner: S, path: P) -> Result<Self, CrawlError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|err| cassette_error(path, err))?;
+
+        Ok(Self {
+            inner,
+            path: path.to_path_buf(),
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    fn record(&self, entry: &CassetteEntry) -> Result<(), CrawlError> {
+        let line = serde_json::to_string(entry).map_err(|err| cassette_error(&self.path, err))?;
+
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{}", line)
+            .and_then(|_| writer.flush())
+            .map_err(|err| cassette_error(&self.path, err))
+    }
+}
+
+#[async_trait]
+impl<S: TransactionSource> TransactionSource for CassetteRecorder<S> {
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+        let request = SignaturesRequest::new(address, &config);
+        let response = self.inner.get_signatures_for_address(address, config).await;
+
+        self.record(&CassetteEntry::Signatures {
+            request,
+            response: &response,
+        })?;
+
+        response
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+        let response = self.inner.get_transaction(signature).await;
+
+        self.record(&CassetteEntry::Transaction {
+            signature: signature.to_string(),
+            response: &response,
+        })?;
+
+        response
+    }
+
+    async fn get_transactions(
+        &self,
+        signatures: &[Signature],
+    ) -> Vec<Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>> {
+        let responses = self.inner.get_transactions(signatures).await;
+
+        responses
+            .into_iter()
+            .zip(signatures)
+            .map(|(response, signature)| {
+                self.record(&CassetteEntry::Transaction {
+                    signature: signature.to_string(),
+                    response: &response,
+                })?;
+                response
+            })
+            .collect()
+    }
+
+    fn backoff(&self) -> bool {
+        self.inner.backoff()
+    }
+}
+
+/// Serves a crawl entirely from a cassette written by `CassetteRecorder`. Responses for the same request are
+/// replayed in the order they were recorded, so retries see the same errors the original run saw; once only
+/// the last response is left it is returned for every further request.
+pub struct CassetteSource {
+    signatures: HashMap<SignaturesRequest, Mutex<VecDeque<SignaturesResponse>>>,
+    transactions: HashMap<String, Mutex<VecDeque<TransactionResponse>>>,
+}
+
+impl CassetteSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, CrawlError> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path).map_err(|err| cassette_error(path, err))?;
+
+        let mut signatures: HashMap<_, VecDeque<_>> = HashMap::new();
+        let mut transactions: HashMap<_, VecDeque<_>> = HashMap::new();
+
+        for line in data.lines().filter(|line| !line.trim().is_empty()) {
+            let entry = serde_json::from_str(line).map_err(|err| cassette_error(path, err))?;
+            match entry {
+                ReplayEntry::Signatures { request, response } => {
+                    signatures.entry(request).or_default().push_back(response)
+                }
+                ReplayEntry::Transaction {
+                    signature,
+                    response,
+                } => transactions
+                    .entry(signature)
+                    .or_default()
+                    .push_back(*response),
+            }
+        }
+
+        Ok(Self {
+            signatures: signatures
+                .into_iter()
+                .map(|(k, v)| (k, Mutex::new(v)))
+                .collect(),
+            transactions: transactions
+                .into_iter()
+                .map(|(k, v)| (k, Mutex::new(v)))
+                .collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl TransactionSource for CassetteSource {
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+        let request = SignaturesRequest::new(address, &config);
+
+        match self.signatures.get(&request) {
+            Some(responses) => next_response(responses, |r| match r {
+                Ok(sigs) => Ok(sigs.clone()),
+                Err(err) => Err(err.clone()),
+            }),
+            None => Err(CrawlError::NotInCassette(format!("{:?}", request))),
+        }
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+        match self.transactions.get(&signature.to_string()) {
+            Some(responses) => next_response(responses, |r| match r {
+                Ok(tx) => Ok(clone_transaction(tx)),
+                Err(err) => Err(err.clone()),
+            }),
+            None => Err(CrawlError::TransactionNotFound(signature.to_string())),
+        }
+    }
+
+    /// Recorded errors are replayed without the delays the original run waited between retries.
+    fn backoff(&self) -> bool {
+        false
+    }
+}
+
+fn next_response<T, F>(responses: &Mutex<VecDeque<T>>, clone: F) -> T
+where
+    F: Fn(&T) -> T,
+{
+    let mut responses = responses.lock().unwrap();
+    if responses.len() > 1 {
+        responses.pop_front().unwrap()
+    } else {
+        clone(&responses[0])
+    }
+}
+
+fn cassette_error<E: ToString>(path: &Path, err: E) -> CrawlError {
+    CrawlError::SourceFailed(err.to_string(), path.display().to_string())
+}
@@ -0,0 +1,106 @@
+use serde::de::DeserializeOwned;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::*;
+
+/// A transaction source that reads saved `EncodedConfirmedTransactionWithStatusMeta` values from disk.
+/// The path can either be a directory of `.json` files with one transaction each, or a JSONL file
+/// with one transaction per line. An unfinished last line, as left by an interrupted write, is ignored.
+/// All transactions are loaded when the source is opened.
+pub struct FileSource {
+    inner: MemorySource,
+}
+
+impl FileSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, CrawlError> {
+        let path = path.as_ref();
+
+        let transactions = if path.is_dir() {
+            read_dir(path)?
+        } else {
+            read_jsonl(path)?
+        };
+
+        Ok(Self {
+            inner: MemorySource::new(transactions),
+        })
+    }
+
+    /// The transactions held by this source.
+    pub fn transactions(&self) -> &[EncodedConfirmedTransactionWithStatusMeta] {
+        self.inner.transactions()
+    }
+}
+
+#[async_trait]
+impl TransactionSource for FileSource {
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+        self.inner.get_signatures_for_address(address, config).await
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+        self.inner.get_transaction(signature).await
+    }
+}
+
+fn read_dir(path: &Path) -> Result<Vec<EncodedConfirmedTransactionWithStatusMeta>, CrawlError> {
+    let mut paths = fs::read_dir(path)
+        .map_err(|err| source_error(path, err))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()
+        .map_err(|err| source_error(path, err))?;
+
+    paths.retain(|p| p.extension().map(|ext| ext == "json").unwrap_or(false));
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|p| {
+            let data = fs::read(p).map_err(|err| source_error(p, err))?;
+            serde_json::from_slice(&data).map_err(|err| source_error(p, err))
+        })
+        .collect()
+}
+
+fn read_jsonl(path: &Path) -> Result<Vec<EncodedConfirmedTransactionWithStatusMeta>, CrawlError> {
+    let data = fs::read_to_string(path).map_err(|err| source_error(path, err))?;
+
+    parse_jsonl(&data).map_err(|err| source_error(path, err))
+}
+
+/// Parse JSON Lines, skipping blank lines. A last line without a trailing newline that doesn't parse is ignored,
+/// since it is what an interrupted append leaves behind.
+pub(crate) fn parse_jsonl<T: DeserializeOwned>(data: &str) -> Result<Vec<T>, serde_json::Error> {
+    let (complete, partial) = match data.rfind('\n') {
+        Some(end) => data.split_at(end + 1),
+        None => ("", data),
+    };
+
+    let mut items = complete
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<T>, _>>()?;
+
+    if !partial.trim().is_empty() {
+        if let Ok(item) = serde_json::from_str(partial) {
+            items.push(item);
+        }
+    }
+
+    Ok(items)
+}
+
+fn source_error<E: ToString>(path: &Path, err: E) -> CrawlError {
+    CrawlError::SourceFailed(err.to_string(), path.display().to_string())
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// A transaction source backed by a list of transactions held in memory. Useful for testing filters
+/// against known transactions without a live node.
+pub struct MemorySource {
+    transactions: Vec<EncodedConfirmedTransactionWithStatusMeta>,
+    index: HashMap<String, usize>,
+}
+
+impl MemorySource {
+    pub fn new(transactions: Vec<EncodedConfirmedTransactionWithStatusMeta>) -> Self {
+        let index = transactions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, tx)| transaction_signature(tx).map(|sig| (sig.to_string(), i)))
+            .collect();
+
+        Self {
+            transactions,
+            index,
+        }
+    }
+
+    /// The transactions held by this source.
+    pub fn transactions(&self) -> &[EncodedConfirmedTransactionWithStatusMeta] {
+        &self.transactions
+    }
+}
+
+#[async_trait]
+impl TransactionSource for MemorySource {
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+        Ok(page_signatures(self.transactions.iter(), address, config))
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+        self.index
+            .get(&signature.to_string())
+            .map(|i| clone_transaction(&self.transactions[*i]))
+            .ok_or_else(|| CrawlError::TransactionNotFound(signature.to_string()))
+    }
+}
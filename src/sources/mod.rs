@@ -0,0 +1,214 @@
+use async_trait::async_trait;
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction};
+use std::cmp::Reverse;
+
+use crate::{constants::*, errors::CrawlError, filters::transaction_account_keys};
+
+pub mod cassette;
+pub mod file;
+pub mod memory;
+pub mod rpc;
+
+pub use cassette::*;
+pub use file::*;
+pub use memory::*;
+
+/// This trait defines where a Crawler gets its data from. It is implemented for the Solana `RpcClient`,
+/// and `FileSource` and `MemorySource` allow running crawls offline against saved transactions.
+#[async_trait]
+pub trait TransactionSource: Send + Sync {
+    /// List the signatures of transactions involving an address, newest first, following the paging rules
+    /// of the `getSignaturesForAddress` RPC call.
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError>;
+
+    /// Get a single transaction by its signature.
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>;
+
+    /// Get several transactions, returning one result per signature in the same order. Sources that can
+    /// fetch many transactions in one request override this; by default they are fetched one at a time.
+    /// When the request fails as a whole, every result is a `BatchFailed` error, or `BatchRejected` if sending
+    /// the same request again won't help.
+    async fn get_transactions(
+        &self,
+        signatures: &[Signature],
+    ) -> Vec<Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>> {
+        let mut results = Vec::with_capacity(signatures.len());
+        for signature in signatures {
+            results.push(self.get_transaction(signature).await);
+        }
+        results
+    }
+
+    /// Whether the crawler waits between retries of a failed request. Sources that replay recorded responses
+    /// return false, since waiting doesn't change what they return.
+    fn backoff(&self) -> bool {
+        true
+    }
+}
+
+/// Returns the signature of a transaction, which is its first signature.
+pub fn transaction_signature(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Option<&str> {
+    match &tx.transaction.transaction {
+        EncodedTransaction::Json(ui_tx) => ui_tx.signatures.first().map(|sig| sig.as_str()),
+        _ => None,
+    }
+}
+
+/// `EncodedConfirmedTransactionWithStatusMeta` doesn't implement Clone, but all of its fields do.
+pub fn clone_transaction(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> EncodedConfirmedTransactionWithStatusMeta {
+    EncodedConfirmedTransactionWithStatusMeta {
+        slot: tx.slot,
+        transaction: tx.transaction.clone(),
+        block_time: tx.block_time,
+    }
+}
+
+/// Emulates `getSignaturesForAddress` over a set of transactions: only transactions that include the address
+/// are listed, newest first, starting after `before`, stopping at `until` and returning at most `limit` signatures.
+pub(crate) fn page_signatures<'a, I>(
+    transactions: I,
+    address: &Pubkey,
+    config: GetConfirmedSignaturesForAddress2Config,
+) -> Vec<RpcConfirmedTransactionStatusWithSignature>
+where
+    I: Iterator<Item = &'a EncodedConfirmedTransactionWithStatusMeta>,
+{
+    let address = address.to_string();
+
+    let mut statuses: Vec<RpcConfirmedTransactionStatusWithSignature> = transactions
+        .filter(|tx| transaction_account_keys(tx).contains(&address))
+        .filter_map(|tx| {
+            let signature = transaction_signature(tx)?;
+            let err = tx
+                .transaction
+                .meta
+                .as_ref()
+                .and_then(|meta| meta.err.clone());
+            Some(RpcConfirmedTransactionStatusWithSignature {
+                signature: signature.to_string(),
+                slot: tx.slot,
+                err,
+                memo: None,
+                block_time: tx.block_time,
+                confirmation_status: None,
+            })
+        })
+        .collect();
+
+    // Newest first, keeping the original order within a slot.
+    statuses.sort_by_key(|s| Reverse(s.slot));
+
+    let before = config.before.map(|sig| sig.to_string());
+    let until = config.until.map(|sig| sig.to_string());
+    let limit = config
+        .limit
+        .unwrap_or(SIGNATURES_PAGE_LIMIT)
+        .min(SIGNATURES_PAGE_LIMIT);
+
+    let start = match before {
+        Some(before) => match statuses.iter().position(|s| s.signature == before) {
+            Some(index) => index + 1,
+            None => return Vec::new(),
+        },
+        None => 0,
+    };
+
+    statuses
+        .into_iter()
+        .skip(start)
+        .take_while(|s| Some(&s.signature) != until.as_ref())
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::transaction;
+
+    fn signatures(
+        transactions: &[EncodedConfirmedTransactionWithStatusMeta],
+        address: &Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: Option<usize>,
+    ) -> Vec<String> {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until,
+            limit,
+            commitment: None,
+        };
+        page_signatures(transactions.iter(), address, config)
+            .into_iter()
+            .map(|status| status.signature)
+            .collect()
+    }
+
+    #[test]
+    fn page_signatures_lists_newest_first_within_bounds() {
+        let address = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let sigs = (0..5).map(|_| Signature::new_unique()).collect::<Vec<_>>();
+
+        let tx = |sig: &Signature, slot, key: &Pubkey| {
+            transaction(&sig.to_string(), slot, &[&key.to_string()], &[], &[], &[])
+        };
+        let transactions = vec![
+            tx(&sigs[0], 10, &address),
+            tx(&sigs[1], 30, &address),
+            tx(&sigs[2], 20, &other),
+            tx(&sigs[3], 20, &address),
+            tx(&sigs[4], 20, &address),
+        ];
+        let expected = |indices: &[usize]| {
+            indices
+                .iter()
+                .map(|i| sigs[*i].to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // Transactions in the same slot keep their order, and ones without the address are left out.
+        assert_eq!(
+            signatures(&transactions, &address, None, None, None),
+            expected(&[1, 3, 4, 0])
+        );
+        assert_eq!(
+            signatures(&transactions, &address, Some(sigs[3]), None, None),
+            expected(&[4, 0])
+        );
+        assert_eq!(
+            signatures(&transactions, &address, None, Some(sigs[0]), None),
+            expected(&[1, 3, 4])
+        );
+        assert_eq!(
+            signatures(&transactions, &address, None, None, Some(2)),
+            expected(&[1, 3])
+        );
+        // An unknown `before` signature lists nothing, like a node would.
+        assert_eq!(
+            signatures(
+                &transactions,
+                &address,
+                Some(Signature::new_unique()),
+                None,
+                None
+            ),
+            expected(&[])
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_transaction_status::UiTransactionEncoding;
+use std::{sync::OnceLock, time::Duration};
+
+use super::*;
+
+/// The non-blocking client is used so fetches run concurrently on the async runtime
+/// instead of blocking its worker threads.
+#[async_trait]
+impl TransactionSource for RpcClient {
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+        self.get_signatures_for_address_with_config(address, config)
+            .await
+            .map_err(|err| CrawlError::ClientError(err.to_string(), address.to_string()))
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+        self.get_transaction_with_config(signature, transaction_config())
+            .await
+            .map_err(|err| CrawlError::ClientError(err.to_string(), signature.to_string()))
+    }
+
+    /// Fetches all the transactions with a single JSON-RPC batch request to the client's URL.
+    async fn get_transactions(
+        &self,
+        signatures: &[Signature],
+    ) -> Vec<Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>> {
+        match get_transaction_batch(self, signatures).await {
+            Ok(results) => results,
+            // The whole request failed, e.g. because the node is rate limiting, so every signature fails with it.
+            Err(err) => signatures.iter().map(|_| Err(err.clone())).collect(),
+        }
+    }
+}
+
+/// The config of every `getTransaction` request, whether it is sent on its own or in a batch.
+fn transaction_config() -> RpcTransactionConfig {
+    RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::JsonParsed),
+        commitment: None,
+        max_supported_transaction_version: Some(0),
+    }
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+    id: usize,
+    result: Option<EncodedConfirmedTransactionWithStatusMeta>,
+    error: Option<Value>,
+}
+
+/// The RpcClient doesn't support batch requests, so they're sent over a shared HTTP client. The RpcClient doesn't
+/// expose its settings either, so this client is built like the RpcClient's default one: with a 30 second timeout
+/// and the same `solana-client` header.
+fn http_client() -> Result<&'static reqwest::Client, String> {
+    static CLIENT: OnceLock<Result<reqwest::Client, String>> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            let mut headers = HeaderMap::new();
+            let version = format!("rust/{}", solana_version::Version::default());
+            headers.insert(
+                HeaderName::from_static("solana-client"),
+                HeaderValue::from_str(&version).map_err(|err| err.to_string())?,
+            );
+
+            let timeout = Duration::from_secs(RPC_TIMEOUT_SECS);
+            reqwest::Client::builder()
+                .default_headers(headers)
+                .timeout(timeout)
+                .pool_idle_timeout(timeout)
+                .build()
+                .map_err(|err| err.to_string())
+        })
+        .as_ref()
+        .map_err(|err| err.clone())
+}
+
+/// Send a batch of `getTransaction` requests. A client error response other than 429 Too Many Requests, e.g. from a
+/// node that doesn't accept batches this large, fails with `BatchRejected` since sending it again won't help.
+async fn get_transaction_batch(
+    client: &RpcClient,
+    signatures: &[Signature],
+) -> Result<Vec<Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>>, CrawlError> {
+    let config = transaction_config();
+
+    // Each request's id is its index so responses, which can arrive in any order, map back to their signature.
+    let requests: Vec<Value> = signatures
+        .iter()
+        .enumerate()
+        .map(|(id, sig)| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "getTransaction",
+                "params": [sig.to_string(), config],
+            })
+        })
+        .collect();
+
+    let response = http_client()
+        .map_err(CrawlError::BatchFailed)?
+        .post(client.url())
+        .json(&requests)
+        .send()
+        .await
+        .map_err(batch_error)?;
+
+    let status = response.status();
+    if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS {
+        return Err(CrawlError::BatchRejected(status.to_string()));
+    }
+
+    let responses: Vec<BatchResponse> = response
+        .error_for_status()
+        .map_err(batch_error)?
+        .json()
+        .await
+        .map_err(batch_error)?;
+
+    let mut results: Vec<Option<Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>>> =
+        signatures.iter().map(|_| None).collect();
+
+    for response in responses {
+        let signature = match signatures.get(response.id) {
+            Some(sig) => sig.to_string(),
+            None => continue,
+        };
+        let result = match (response.result, response.error) {
+            (_, Some(error)) => Err(CrawlError::ClientError(error.to_string(), signature)),
+            (Some(tx), None) => Ok(tx),
+            (None, None) => Err(CrawlError::ClientError(
+                "transaction not available".to_string(),
+                signature,
+            )),
+        };
+        results[response.id] = Some(result);
+    }
+
+    Ok(results
+        .into_iter()
+        .zip(signatures)
+        .map(|(result, sig)| {
+            result.unwrap_or_else(|| {
+                Err(CrawlError::ClientError(
+                    "missing from batch response".to_string(),
+                    sig.to_string(),
+                ))
+            })
+        })
+        .collect())
+}
+
+fn batch_error(err: reqwest::Error) -> CrawlError {
+    CrawlError::BatchFailed(err.to_string())
+}
@@ -0,0 +1,34 @@
+use solana_sdk::signature::Signature;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+use crate::{errors::CrawlError, records::CrawlRecord};
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
+
+/// This trait defines the interface for persisting crawl data between runs. When a Crawler has a store,
+/// transactions already in it are not fetched again, and the transactions it fetches are saved to it along with
+/// whether they passed the transaction filters and the records extracted from them.
+pub trait TransactionStore: Send + Sync {
+    /// Returns the transactions among `signatures` that are already stored.
+    fn load_transactions(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<(Signature, EncodedConfirmedTransactionWithStatusMeta)>, CrawlError>;
+
+    fn save_transactions(
+        &self,
+        transactions: &[(Signature, EncodedConfirmedTransactionWithStatusMeta)],
+    ) -> Result<(), CrawlError>;
+
+    /// Save whether each transaction passed the transaction filters and the records extracted from them,
+    /// replacing any results an earlier run stored for the same transactions.
+    fn save_results(
+        &self,
+        outcomes: &[(String, bool)],
+        records: &[CrawlRecord],
+    ) -> Result<(), CrawlError>;
+}
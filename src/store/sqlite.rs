@@ -0,0 +1,437 @@
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_sdk::{pubkey::Pubkey, transaction::TransactionError};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use super::*;
+use crate::{
+    blocking::run_blocking, constants::SIGNATURES_PAGE_LIMIT, filters::transaction_account_keys,
+    sources::TransactionSource,
+};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS transactions (
+        signature TEXT PRIMARY KEY,
+        slot INTEGER NOT NULL,
+        block_time INTEGER,
+        err TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS transaction_accounts (
+        address TEXT NOT NULL,
+        signature TEXT NOT NULL,
+        PRIMARY KEY (address, signature)
+    );
+    CREATE TABLE IF NOT EXISTS filter_outcomes (
+        signature TEXT PRIMARY KEY,
+        passed INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS records (
+        signature TEXT NOT NULL,
+        label TEXT NOT NULL,
+        value TEXT NOT NULL,
+        slot INTEGER NOT NULL,
+        block_time INTEGER,
+        instruction_index INTEGER,
+        inner_instruction_index INTEGER,
+        program_id TEXT
+    );
+    CREATE INDEX IF NOT EXISTS records_signature ON records (signature);
+    CREATE INDEX IF NOT EXISTS records_label ON records (label);
+";
+
+/// A SQLite database holding fetched transactions, keyed by signature with their slot, block time and error, along
+/// with filter outcomes and extracted records. Set it on a Crawler with `set_store` so repeated crawls only fetch new
+/// transactions, or pass it to `Crawler::new` as a transaction source to run new filters over the stored
+/// transactions offline. The records table can be queried directly with any SQLite client.
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    /// Open the database at `path`, creating it and its tables if they don't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, CrawlError> {
+        let conn = Connection::open(path).map_err(store_error)?;
+        conn.execute_batch(SCHEMA).map_err(store_error)?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Run a query from async code on tokio's blocking thread pool, so waiting for the connection doesn't
+    /// stall the runtime's worker threads.
+    async fn with_conn<T, F>(&self, query: F) -> Result<T, CrawlError>
+    where
+        F: FnOnce(&Connection) -> Result<T, CrawlError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let conn = self.conn.clone();
+        run_blocking(move || query(&conn.lock().unwrap())).await
+    }
+
+    /// Returns true if the transaction is stored.
+    pub fn contains(&self, signature: &str) -> Result<bool, CrawlError> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT 1 FROM transactions WHERE signature = ?1",
+            params![signature],
+            |_| Ok(()),
+        )
+        .optional()
+        .map(|row| row.is_some())
+        .map_err(store_error)
+    }
+
+    /// Returns every stored record, ordered by slot.
+    pub fn records(&self) -> Result<Vec<CrawlRecord>, CrawlError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT label, value, signature, slot, block_time, instruction_index,
+                    inner_instruction_index, program_id
+                 FROM records ORDER BY slot, rowid",
+            )
+            .map_err(store_error)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, Option<i64>>(4)?,
+                    row.get::<_, Option<i64>>(5)?,
+                    row.get::<_, Option<i64>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
+                ))
+            })
+            .map_err(store_error)?;
+
+        let records = rows
+            .map(|row| {
+                let (label, value, signature, slot, block_time, index, inner_index, program_id) =
+                    row.map_err(store_error)?;
+                Ok(CrawlRecord {
+                    label,
+                    value: serde_json::from_str(&value).map_err(store_error)?,
+                    signature,
+                    slot: slot as u64,
+                    block_time,
+                    instruction_index: index.map(|index| index as usize),
+                    inner_instruction_index: inner_index.map(|index| index as usize),
+                    program_id,
+                })
+            })
+            .collect::<Result<Vec<CrawlRecord>, CrawlError>>()?;
+
+        Ok(records)
+    }
+
+    /// Returns the signatures of the stored transactions that passed the transaction filters on the last run
+    /// that processed them.
+    pub fn passed_signatures(&self) -> Result<Vec<String>, CrawlError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT signature FROM filter_outcomes WHERE passed = 1")
+            .map_err(store_error)?;
+
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(store_error)?;
+
+        let signatures = rows
+            .map(|row| row.map_err(store_error))
+            .collect::<Result<Vec<String>, CrawlError>>()?;
+
+        Ok(signatures)
+    }
+}
+
+impl TransactionStore for SqliteStore {
+    fn load_transactions(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<(Signature, EncodedConfirmedTransactionWithStatusMeta)>, CrawlError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut transactions = Vec::new();
+        for signature in signatures {
+            if let Some(tx) = load_transaction(&conn, signature)? {
+                transactions.push((*signature, tx));
+            }
+        }
+
+        Ok(transactions)
+    }
+
+    fn save_transactions(
+        &self,
+        transactions: &[(Signature, EncodedConfirmedTransactionWithStatusMeta)],
+    ) -> Result<(), CrawlError> {
+        let mut conn = self.conn.lock().unwrap();
+        let db_tx = conn.transaction().map_err(store_error)?;
+
+        for (signature, tx) in transactions {
+            let signature = signature.to_string();
+            let data = serde_json::to_string(tx).map_err(store_error)?;
+            let err = tx
+                .transaction
+                .meta
+                .as_ref()
+                .and_then(|meta| meta.err.as_ref())
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(store_error)?;
+            // Update in place rather than replace, so the rowid that orders a slot's transactions stays put.
+            db_tx
+                .execute(
+                    "INSERT INTO transactions (signature, slot, block_time, err, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT(signature) DO UPDATE SET slot = excluded.slot,
+                        block_time = excluded.block_time, err = excluded.err, data = excluded.data",
+                    params![signature, tx.slot as i64, tx.block_time, err, data],
+                )
+                .map_err(store_error)?;
+
+            for address in transaction_account_keys(tx) {
+                db_tx
+                    .execute(
+                        "INSERT OR IGNORE INTO transaction_accounts (address, signature)
+                         VALUES (?1, ?2)",
+                        params![address, signature],
+                    )
+                    .map_err(store_error)?;
+            }
+        }
+
+        db_tx.commit().map_err(store_error)
+    }
+
+    fn save_results(
+        &self,
+        outcomes: &[(String, bool)],
+        records: &[CrawlRecord],
+    ) -> Result<(), CrawlError> {
+        let mut conn = self.conn.lock().unwrap();
+        let db_tx = conn.transaction().map_err(store_error)?;
+
+        for (signature, passed) in outcomes {
+            db_tx
+                .execute(
+                    "INSERT OR REPLACE INTO filter_outcomes (signature, passed) VALUES (?1, ?2)",
+                    params![signature, passed],
+                )
+                .map_err(store_error)?;
+            db_tx
+                .execute(
+                    "DELETE FROM records WHERE signature = ?1",
+                    params![signature],
+                )
+                .map_err(store_error)?;
+        }
+
+        for record in records {
+            let value = serde_json::to_string(&record.value).map_err(store_error)?;
+            db_tx
+                .execute(
+                    "INSERT INTO records (signature, label, value, slot, block_time, instruction_index,
+                        inner_instruction_index, program_id)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        record.signature,
+                        record.label,
+                        value,
+                        record.slot as i64,
+                        record.block_time,
+                        record.instruction_index.map(|index| index as i64),
+                        record.inner_instruction_index.map(|index| index as i64),
+                        record.program_id,
+                    ],
+                )
+                .map_err(store_error)?;
+        }
+
+        db_tx.commit().map_err(store_error)
+    }
+}
+
+/// Serves stored transactions, so a crawl can be re-run with new filters without network access.
+#[async_trait]
+impl TransactionSource for SqliteStore {
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+        let address = address.to_string();
+        self.with_conn(move |conn| signature_page(conn, &address, &config))
+            .await
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+        let signature = *signature;
+        self.with_conn(move |conn| load_transaction(conn, &signature))
+            .await?
+            .ok_or_else(|| CrawlError::TransactionNotFound(signature.to_string()))
+    }
+}
+
+fn load_transaction(
+    conn: &Connection,
+    signature: &Signature,
+) -> Result<Option<EncodedConfirmedTransactionWithStatusMeta>, CrawlError> {
+    let data = conn
+        .prepare_cached("SELECT data FROM transactions WHERE signature = ?1")
+        .and_then(|mut stmt| {
+            stmt.query_row(params![signature.to_string()], |row| {
+                row.get::<_, String>(0)
+            })
+            .optional()
+        })
+        .map_err(store_error)?;
+
+    data.map(|data| serde_json::from_str(&data).map_err(store_error))
+        .transpose()
+}
+
+/// Emulates `getSignaturesForAddress` over the stored transactions like `MemorySource` does, reading only the
+/// signature, slot, block time and error columns. Transactions in the same slot are listed in the order they
+/// were stored.
+fn signature_page(
+    conn: &Connection,
+    address: &str,
+    config: &GetConfirmedSignaturesForAddress2Config,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+    // The position of a cursor signature among the address's transactions, as its slot and rowid.
+    let position = |signature: &Signature| {
+        conn.query_row(
+            "SELECT t.slot, t.rowid FROM transactions t
+             JOIN transaction_accounts a ON a.signature = t.signature
+             WHERE a.address = ?1 AND t.signature = ?2",
+            params![address, signature.to_string()],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+        )
+        .optional()
+        .map_err(store_error)
+    };
+
+    let before = match &config.before {
+        Some(signature) => match position(signature)? {
+            Some(position) => Some(position),
+            // Like a node, nothing is listed before a signature that isn't known.
+            None => return Ok(Vec::new()),
+        },
+        None => None,
+    };
+    let until = match &config.until {
+        Some(signature) => position(signature)?,
+        None => None,
+    };
+    let limit = config
+        .limit
+        .unwrap_or(SIGNATURES_PAGE_LIMIT)
+        .min(SIGNATURES_PAGE_LIMIT);
+
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT t.signature, t.slot, t.block_time, t.err FROM transactions t
+             JOIN transaction_accounts a ON a.signature = t.signature
+             WHERE a.address = ?1
+                AND (?2 IS NULL OR t.slot < ?2 OR (t.slot = ?2 AND t.rowid > ?3))
+                AND (?4 IS NULL OR t.slot > ?4 OR (t.slot = ?4 AND t.rowid < ?5))
+             ORDER BY t.slot DESC, t.rowid
+             LIMIT ?6",
+        )
+        .map_err(store_error)?;
+
+    let rows = stmt
+        .query_map(
+            params![
+                address,
+                before.map(|(slot, _)| slot),
+                before.map(|(_, rowid)| rowid),
+                until.map(|(slot, _)| slot),
+                until.map(|(_, rowid)| rowid),
+                limit as i64,
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        )
+        .map_err(store_error)?;
+
+    rows.map(|row| {
+        let (signature, slot, block_time, err) = row.map_err(store_error)?;
+        let err = err
+            .map(|err| serde_json::from_str::<TransactionError>(&err))
+            .transpose()
+            .map_err(store_error)?;
+        Ok(RpcConfirmedTransactionStatusWithSignature {
+            signature,
+            slot: slot as u64,
+            err,
+            memo: None,
+            block_time,
+            confirmation_status: None,
+        })
+    })
+    .collect()
+}
+
+fn store_error<E: ToString>(err: E) -> CrawlError {
+    CrawlError::StoreFailed(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::transaction;
+
+    #[tokio::test]
+    async fn restoring_a_transaction_keeps_its_place_in_the_slot() {
+        let address = Pubkey::new_unique();
+        let sigs = (0..3).map(|_| Signature::new_unique()).collect::<Vec<_>>();
+        let tx = |sig: &Signature| {
+            let tx = transaction(&sig.to_string(), 10, &[&address.to_string()], &[], &[], &[]);
+            (*sig, tx)
+        };
+
+        let store = SqliteStore::open(":memory:").unwrap();
+        store
+            .save_transactions(&sigs.iter().map(tx).collect::<Vec<_>>())
+            .unwrap();
+        store.save_transactions(&[tx(&sigs[0])]).unwrap();
+
+        let listed = store
+            .get_signatures_for_address(
+                &address,
+                GetConfirmedSignaturesForAddress2Config::default(),
+            )
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|status| status.signature)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            listed,
+            sigs.iter().map(ToString::to_string).collect::<Vec<_>>()
+        );
+    }
+}
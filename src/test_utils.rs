@@ -0,0 +1,55 @@
+//! Builders for the transactions used by unit tests.
+
+use serde_json::{json, Value};
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+/// An instruction given by its program id and accounts, with empty data.
+pub(crate) type TestIx<'a> = (&'a str, &'a [&'a str]);
+
+/// A successful JSON parsed transaction. Inner instructions are grouped by the index of the top-level
+/// instruction that invoked them.
+pub(crate) fn transaction(
+    signature: &str,
+    slot: u64,
+    account_keys: &[&str],
+    instructions: &[TestIx],
+    inner_instructions: &[(u8, &[TestIx])],
+    logs: &[&str],
+) -> EncodedConfirmedTransactionWithStatusMeta {
+    let instruction = |(program_id, accounts): &TestIx| json!({ "programId": program_id, "accounts": accounts, "data": "" });
+    let account_keys = account_keys
+        .iter()
+        .map(|key| json!({ "pubkey": key, "writable": true, "signer": false }))
+        .collect::<Vec<Value>>();
+    let inner_instructions = inner_instructions
+        .iter()
+        .map(|(index, ixs)| {
+            json!({ "index": index, "instructions": ixs.iter().map(instruction).collect::<Vec<_>>() })
+        })
+        .collect::<Vec<Value>>();
+
+    serde_json::from_value(json!({
+        "slot": slot,
+        "blockTime": null,
+        "transaction": {
+            "signatures": [signature],
+            "message": {
+                "accountKeys": account_keys,
+                "recentBlockhash": "11111111111111111111111111111111",
+                "instructions": instructions.iter().map(instruction).collect::<Vec<_>>(),
+            },
+        },
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "innerInstructions": inner_instructions,
+            "logMessages": logs,
+            "preTokenBalances": [],
+            "postTokenBalances": [],
+        },
+    }))
+    .unwrap()
+}
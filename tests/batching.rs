@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_crawler::{
+    constants::MAX_RETRIES,
+    crawler::Crawler,
+    errors::CrawlError,
+    sources::{FileSource, TransactionSource},
+};
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+
+use common::{candy_machine, FIXTURE};
+
+/// Fails the first `batch_failures` batch requests as a whole and drops the last result of every other batch.
+/// Failed batches are rejected outright if `rejected` is set.
+struct BatchSource {
+    inner: FileSource,
+    batch_failures: usize,
+    rejected: bool,
+    batches: AtomicUsize,
+    single_requests: AtomicUsize,
+}
+
+impl BatchSource {
+    fn new(batch_failures: usize) -> Self {
+        Self {
+            inner: FileSource::open(FIXTURE).unwrap(),
+            batch_failures,
+            rejected: false,
+            batches: AtomicUsize::new(0),
+            single_requests: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionSource for BatchSource {
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+        self.inner.get_signatures_for_address(address, config).await
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+        self.single_requests.fetch_add(1, Ordering::SeqCst);
+        self.inner.get_transaction(signature).await
+    }
+
+    async fn get_transactions(
+        &self,
+        signatures: &[Signature],
+    ) -> Vec<Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError>> {
+        if self.batches.fetch_add(1, Ordering::SeqCst) < self.batch_failures {
+            let err = match self.rejected {
+                true => CrawlError::BatchRejected("413 Payload Too Large".to_string()),
+                false => CrawlError::BatchFailed("429 Too Many Requests".to_string()),
+            };
+            return signatures.iter().map(|_| Err(err.clone())).collect();
+        }
+
+        let mut results = Vec::new();
+        for signature in &signatures[..signatures.len() - 1] {
+            results.push(self.inner.get_transaction(signature).await);
+        }
+        results
+    }
+
+    fn backoff(&self) -> bool {
+        false
+    }
+}
+
+#[tokio::test]
+async fn failed_batches_are_retried_as_a_whole() {
+    let source = std::sync::Arc::new(BatchSource::new(2));
+    let mut crawler = Crawler::new_arc_client(source.clone(), candy_machine());
+    crawler.set_batch_size(3);
+
+    let (_, report) = crawler.run().await.unwrap();
+
+    assert_eq!(source.batches.load(Ordering::SeqCst), 3);
+    assert_eq!(source.single_requests.load(Ordering::SeqCst), 0);
+    assert_eq!(report.retries, 2);
+
+    // The batch response left out the last signature, which is reported instead of dropped.
+    assert_eq!(report.failed.len(), 1);
+    assert!(matches!(report.failed[0].1, CrawlError::SourceFailed(_, _)));
+}
+
+#[tokio::test]
+async fn batches_that_keep_failing_are_fetched_one_at_a_time() {
+    let source = std::sync::Arc::new(BatchSource::new(usize::MAX));
+    let mut crawler = Crawler::new_arc_client(source.clone(), candy_machine());
+    crawler.set_batch_size(3);
+
+    let (_, report) = crawler.run().await.unwrap();
+
+    assert_eq!(source.batches.load(Ordering::SeqCst), MAX_RETRIES + 1);
+    assert_eq!(source.single_requests.load(Ordering::SeqCst), 3);
+    assert_eq!(report.retries, MAX_RETRIES);
+    assert!(report.is_complete());
+}
+
+#[tokio::test]
+async fn rejected_batches_are_fetched_one_at_a_time_without_retrying() {
+    let mut source = BatchSource::new(usize::MAX);
+    source.rejected = true;
+    let source = std::sync::Arc::new(source);
+    let mut crawler = Crawler::new_arc_client(source.clone(), candy_machine());
+    crawler.set_batch_size(3);
+
+    let (_, report) = crawler.run().await.unwrap();
+
+    assert_eq!(source.batches.load(Ordering::SeqCst), 1);
+    assert_eq!(source.single_requests.load(Ordering::SeqCst), 3);
+    assert_eq!(report.retries, 0);
+    assert!(report.is_complete());
+}
@@ -0,0 +1,49 @@
+use std::{fs, io::Write};
+
+mod common;
+
+use common::{crawler, FIXTURE};
+
+#[tokio::test]
+async fn interrupted_cache_writes_are_recovered() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = dir.path().join("cache.jsonl");
+
+    // A cache holding the first transaction and part of the second, as left by a crash mid-write.
+    let fixture = fs::read_to_string(FIXTURE).unwrap();
+    let mut lines = fixture.lines();
+    let mut file = fs::File::create(&cache).unwrap();
+    writeln!(file, "{}", lines.next().unwrap()).unwrap();
+    write!(file, "{}", &lines.next().unwrap()[..100]).unwrap();
+    drop(file);
+
+    let crawler = crawler();
+    let (partial, _) = crawler.analyze_cache(&cache).unwrap();
+    assert_eq!(partial["mint"].len(), 1);
+
+    let report = crawler.fetch_to_cache(&cache).await.unwrap();
+    assert!(report.is_complete());
+    assert_eq!(fs::read_to_string(&cache).unwrap().lines().count(), 3);
+
+    let (cached, _) = crawler.analyze_cache(&cache).unwrap();
+    let (crawled, _) = crawler.run().await.unwrap();
+    assert_eq!(cached, crawled);
+}
+
+#[tokio::test]
+async fn cache_analysis_keeps_to_the_crawl_window() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = dir.path().join("cache.jsonl");
+    crawler().fetch_to_cache(&cache).await.unwrap();
+
+    // The fixture's mints are at slots 300 and 100.
+    for (min_slot, max_slot) in [(150, 400), (50, 250)] {
+        let mut crawler = crawler();
+        crawler.set_min_slot(min_slot).set_max_slot(max_slot);
+
+        let (cached, _) = crawler.analyze_cache(&cache).unwrap();
+        let (crawled, _) = crawler.run().await.unwrap();
+        assert_eq!(cached["mint"].len(), 1);
+        assert_eq!(cached, crawled);
+    }
+}
@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_crawler::{
+    crawler::Crawler,
+    errors::CrawlError,
+    sources::{CassetteRecorder, CassetteSource, FileSource, TransactionSource},
+};
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+mod common;
+
+use common::{candy_machine, FIXTURE};
+
+fn set(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[tokio::test]
+async fn cmv2_mints_replay_from_cassette() {
+    let dir = tempfile::tempdir().unwrap();
+    let cassette = dir.path().join("cmv2_mints.cassette.jsonl");
+
+    let recorder = CassetteRecorder::create(FileSource::open(FIXTURE).unwrap(), &cassette).unwrap();
+    let (recorded, report) = Crawler::get_cmv2_mints(recorder, candy_machine())
+        .await
+        .unwrap();
+    assert!(report.is_complete());
+
+    let (replayed, report) =
+        Crawler::get_cmv2_mints(CassetteSource::open(&cassette).unwrap(), candy_machine())
+            .await
+            .unwrap();
+    assert!(report.is_complete());
+    assert_eq!(replayed, recorded);
+
+    // The bot taxed transaction is filtered out.
+    assert_eq!(
+        replayed["mint"],
+        set(&[
+            "2Kzer5QGZNcmBFvFyvYjDkkkUUderbdyf3ACkMgqREuv",
+            "21AF1UstHjM4PNtLFeH2QJBzJh2W7TzFwd3DTCCnFpoh",
+        ])
+    );
+    assert_eq!(
+        replayed["metadata"],
+        set(&[
+            "GxK3NbCtnmcjfjs5zuXzbwtZKPdFzWPnasR38UmeH6PW",
+            "7vTgrxfJqnGWNCkPFf7txNJnUSLKCy5VZq2rS3gkgVeY",
+        ])
+    );
+}
+
+#[tokio::test]
+async fn cassette_miss_fails_without_retrying() {
+    let dir = tempfile::tempdir().unwrap();
+    let cassette = dir.path().join("cmv2_mints.cassette.jsonl");
+
+    let recorder = CassetteRecorder::create(FileSource::open(FIXTURE).unwrap(), &cassette).unwrap();
+    Crawler::get_cmv2_mints(recorder, candy_machine())
+        .await
+        .unwrap();
+
+    // A different crawl window requests a signature page that was never recorded.
+    let source = Arc::new(CountingSource {
+        inner: CassetteSource::open(&cassette).unwrap(),
+        requests: AtomicUsize::new(0),
+    });
+    let mut crawler = Crawler::new_arc_client(source.clone(), candy_machine());
+    crawler.set_before(Signature::new_unique());
+
+    let result = crawler.run().await;
+
+    assert!(matches!(result, Err(CrawlError::NotInCassette(_))));
+    assert_eq!(source.requests.load(Ordering::SeqCst), 1);
+}
+
+/// Counts the requests made to a source.
+struct CountingSource<S> {
+    inner: S,
+    requests: AtomicUsize,
+}
+
+#[async_trait]
+impl<S: TransactionSource> TransactionSource for CountingSource<S> {
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        self.inner.get_signatures_for_address(address, config).await
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        self.inner.get_transaction(signature).await
+    }
+
+    fn backoff(&self) -> bool {
+        self.inner.backoff()
+    }
+}
+
+/// Fails the first request for every transaction, like a node under load.
+struct FlakySource {
+    inner: FileSource,
+    failed: Mutex<HashSet<Signature>>,
+}
+
+#[async_trait]
+impl TransactionSource for FlakySource {
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, CrawlError> {
+        self.inner.get_signatures_for_address(address, config).await
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, CrawlError> {
+        if self.failed.lock().unwrap().insert(*signature) {
+            return Err(CrawlError::ClientError(
+                "node is behind".to_string(),
+                signature.to_string(),
+            ));
+        }
+        self.inner.get_transaction(signature).await
+    }
+}
+
+#[tokio::test]
+async fn recorded_errors_replay_without_backoff() {
+    let dir = tempfile::tempdir().unwrap();
+    let cassette = dir.path().join("flaky.cassette.jsonl");
+
+    let flaky = FlakySource {
+        inner: FileSource::open(FIXTURE).unwrap(),
+        failed: Mutex::new(HashSet::new()),
+    };
+    let recorder = CassetteRecorder::create(flaky, &cassette).unwrap();
+    let (recorded, report) = Crawler::get_cmv2_mints(recorder, candy_machine())
+        .await
+        .unwrap();
+    assert_eq!(report.retries, 3);
+
+    let replay = CassetteSource::open(&cassette).unwrap();
+    assert!(!replay.backoff());
+    let (replayed, report) = Crawler::get_cmv2_mints(replay, candy_machine())
+        .await
+        .unwrap();
+
+    assert_eq!(replayed, recorded);
+    assert_eq!(report.retries, 3);
+}
@@ -0,0 +1,104 @@
+use serde_json::Value;
+use solana_transaction_crawler::{
+    checkpoint::{records_path, Checkpoint},
+    sinks::JsonlSink,
+};
+use std::{fs, io::Write};
+
+mod common;
+
+use common::crawler;
+
+#[tokio::test]
+async fn records_are_kept_out_of_the_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("crawl.json");
+
+    let mut crawler = crawler();
+    crawler.set_checkpoint_path(&path);
+    let (records, _) = crawler.run_records().await.unwrap();
+    assert_eq!(records.len(), 4);
+
+    for line in fs::read_to_string(&path).unwrap().lines() {
+        let entry: Value = serde_json::from_str(line).unwrap();
+        assert!(entry.get("records").is_none());
+        assert!(entry.get("accounts").is_none());
+    }
+
+    let saved = fs::read_to_string(records_path(&path)).unwrap();
+    assert_eq!(saved.lines().count(), records.len());
+}
+
+#[tokio::test]
+async fn resume_drops_records_of_unsaved_batches() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("crawl.json");
+
+    let mut crawler = crawler();
+    crawler.set_checkpoint_path(&path);
+    let (accounts, _) = crawler.run().await.unwrap();
+
+    // Simulate a crash after a record of an unprocessed transaction and part of another were appended.
+    let mut checkpoint = Checkpoint::load(&path).unwrap().unwrap();
+    let signature = checkpoint.signatures[0].clone();
+    checkpoint.processed.remove(&signature);
+    checkpoint.save(&path).unwrap();
+
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(records_path(&path))
+        .unwrap();
+    write!(file, "{{\"label\":\"mint\",\"val").unwrap();
+
+    let (resumed, report) = crawler.resume_from(&path).await.unwrap();
+
+    assert!(report.is_complete());
+    assert_eq!(resumed, accounts);
+
+    let saved = fs::read_to_string(records_path(&path)).unwrap();
+    assert_eq!(saved.lines().count(), 4);
+}
+
+#[tokio::test]
+async fn sink_crawls_keep_records_out_of_the_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("crawl.json");
+
+    let mut crawler = crawler();
+    crawler.set_checkpoint_path(&path);
+    let output = dir.path().join("records.jsonl");
+    let mut sink = JsonlSink::create(&output).unwrap();
+    crawler.run_with_sink(&mut sink).await.unwrap();
+
+    assert_eq!(fs::read_to_string(&output).unwrap().lines().count(), 4);
+    assert!(Checkpoint::load(&path).unwrap().is_some());
+    assert_eq!(fs::read_to_string(records_path(&path)).unwrap(), "");
+}
+
+#[tokio::test]
+async fn checkpoint_appends_each_signature_once_per_step() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("crawl.json");
+
+    let mut crawler = crawler();
+    crawler.set_checkpoint_path(&path);
+    crawler.run().await.unwrap();
+
+    let checkpoint = Checkpoint::load(&path).unwrap().unwrap();
+    assert!(checkpoint.listing_complete);
+    assert_eq!(checkpoint.signatures.len(), 3);
+    assert_eq!(checkpoint.processed.len(), 3);
+
+    // Each signature is written once when it is listed and once when its transaction is processed.
+    let log = fs::read_to_string(&path).unwrap();
+    for signature in &checkpoint.signatures {
+        assert_eq!(log.matches(signature.as_str()).count(), 2);
+    }
+
+    // An entry cut off by a crash is ignored, and resuming saves the checkpoint whole again.
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    write!(file, "{{\"entry\":\"proc").unwrap();
+    let (_, report) = crawler.resume_from(&path).await.unwrap();
+    assert!(report.is_complete());
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 4);
+}
@@ -0,0 +1,19 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_crawler::{crawler::Crawler, sources::FileSource};
+use std::str::FromStr;
+
+/// Three transactions of a Candy Machine v2: two mints and a bot tax in between.
+pub const FIXTURE: &str = "tests/fixtures/cmv2_mints.jsonl";
+pub const CANDY_MACHINE: &str = "6wAp6ZHvA7x9Pn88RQqwSQe7WD1j8cz2Y5F1FDBgN6n9";
+
+pub fn candy_machine() -> Pubkey {
+    Pubkey::from_str(CANDY_MACHINE).unwrap()
+}
+
+/// A crawler for the mints of the fixture's Candy Machine.
+pub fn crawler() -> Crawler {
+    Crawler::create_cmv2_mints(FileSource::open(FIXTURE).unwrap(), candy_machine())
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "sqlite")]
+
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::signature::Signature;
+use solana_transaction_crawler::{
+    crawler::Crawler,
+    sources::{FileSource, TransactionSource},
+    store::SqliteStore,
+};
+use std::str::FromStr;
+
+mod common;
+
+use common::{candy_machine, crawler, FIXTURE};
+
+async fn signatures<S: TransactionSource>(
+    source: &S,
+    before: Option<Signature>,
+    until: Option<Signature>,
+    limit: Option<usize>,
+) -> Vec<String> {
+    let config = GetConfirmedSignaturesForAddress2Config {
+        before,
+        until,
+        limit,
+        commitment: None,
+    };
+    source
+        .get_signatures_for_address(&candy_machine(), config)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|status| status.signature)
+        .collect()
+}
+
+#[tokio::test]
+async fn stored_signatures_page_like_the_original_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("crawl.db");
+    let file = FileSource::open(FIXTURE).unwrap();
+
+    let mut crawler = crawler();
+    crawler.set_store(SqliteStore::open(&db).unwrap());
+    let (accounts, _) = crawler.run().await.unwrap();
+
+    let store = SqliteStore::open(&db).unwrap();
+    let all = signatures(&file, None, None, None).await;
+    assert_eq!(all.len(), 3);
+    assert_eq!(signatures(&store, None, None, None).await, all);
+
+    let sigs = all
+        .iter()
+        .map(|sig| Signature::from_str(sig).unwrap())
+        .collect::<Vec<_>>();
+    for (before, until, limit) in [
+        (Some(sigs[0]), None, None),
+        (None, Some(sigs[2]), None),
+        (Some(sigs[0]), Some(sigs[2]), None),
+        (None, None, Some(2)),
+        (Some(Signature::new_unique()), None, None),
+    ] {
+        assert_eq!(
+            signatures(&store, before, until, limit).await,
+            signatures(&file, before, until, limit).await
+        );
+    }
+
+    // The store alone reproduces the crawl.
+    let offline = Crawler::create_cmv2_mints(store, candy_machine());
+    let (replayed, report) = offline.run().await.unwrap();
+    assert_eq!(replayed, accounts);
+    assert!(report.is_complete());
+    assert_eq!(report.retries, 0);
+}